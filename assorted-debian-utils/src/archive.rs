@@ -18,12 +18,14 @@ use crate::utils::TryFromStrVisitor;
 
 /// "Extensions" to a codename or a suite
 ///
-/// This enum covers the archives for backports, security updates, (old)stable
+/// This enum covers the archives for backports, backports-sloppy, security updates, (old)stable
 /// updates and (old)stable proposed-updates.
 #[derive(Clone, Copy, Debug, PartialEq, Hash, Eq)]
 pub enum Extension {
     /// The backports extension
     Backports,
+    /// The backports-sloppy extension
+    BackportsSloppy,
     /// The security extension
     Security,
     /// The updates extension
@@ -36,6 +38,7 @@ impl AsRef<str> for Extension {
     fn as_ref(&self) -> &str {
         match self {
             Self::Backports => "backports",
+            Self::BackportsSloppy => "backports-sloppy",
             Self::Security => "security",
             Self::Updates => "updates",
             Self::ProposedUpdates => "proposed-updates",
@@ -55,6 +58,7 @@ impl TryFrom<&str> for Extension {
     fn try_from(value: &str) -> Result<Self, Self::Error> {
         match value {
             "backports" => Ok(Self::Backports),
+            "backports-sloppy" => Ok(Self::BackportsSloppy),
             "security" => Ok(Self::Security),
             "updates" => Ok(Self::Updates),
             "proposed-updates" => Ok(Self::ProposedUpdates),
@@ -97,6 +101,8 @@ pub enum Suite {
     Stable(Option<Extension>),
     /// The oldstable suite
     OldStable(Option<Extension>),
+    /// The oldoldstable suite
+    OldOldStable(Option<Extension>),
     /// The experimental suite
     Experimental,
 }
@@ -108,6 +114,7 @@ impl WithExtension for Suite {
             Self::Testing(_) => Self::Testing(Some(extension)),
             Self::Stable(_) => Self::Stable(Some(extension)),
             Self::OldStable(_) => Self::OldStable(Some(extension)),
+            Self::OldOldStable(_) => Self::OldOldStable(Some(extension)),
         }
     }
 
@@ -117,6 +124,7 @@ impl WithExtension for Suite {
             Self::Testing(_) => Self::Testing(None),
             Self::Stable(_) => Self::Stable(None),
             Self::OldStable(_) => Self::OldStable(None),
+            Self::OldOldStable(_) => Self::OldOldStable(None),
         }
     }
 }
@@ -128,12 +136,14 @@ impl Display for Suite {
             Self::Testing(None) => write!(f, "testing"),
             Self::Stable(None) => write!(f, "stable"),
             Self::OldStable(None) => write!(f, "oldstable"),
+            Self::OldOldStable(None) => write!(f, "oldoldstable"),
             Self::Experimental => write!(f, "experimental"),
             Self::Testing(Some(ext)) => write!(f, "testing-{ext}"),
             // The Release file from stable-proposed-updates calls the suite proposed-updaptes.
             Self::Stable(Some(Extension::ProposedUpdates)) => write!(f, "proposed-updates"),
             Self::Stable(Some(ext)) => write!(f, "stable-{ext}"),
             Self::OldStable(Some(ext)) => write!(f, "oldstable-{ext}"),
+            Self::OldOldStable(Some(ext)) => write!(f, "oldoldstable-{ext}"),
         }
     }
 }
@@ -147,6 +157,7 @@ impl TryFrom<&str> for Suite {
             "testing" => Ok(Self::Testing(None)),
             "stable" => Ok(Self::Stable(None)),
             "oldstable" => Ok(Self::OldStable(None)),
+            "oldoldstable" => Ok(Self::OldOldStable(None)),
             // The Release file from stable-proposed-updates calls the suite proposed-updaptes.
             "proposed-updates" => Ok(Self::Stable(Some(Extension::ProposedUpdates))),
             "experimental" => Ok(Self::Experimental),
@@ -157,6 +168,7 @@ impl TryFrom<&str> for Suite {
                     "testing" => Ok(Self::Testing(Some(ext))),
                     "stable" => Ok(Self::Stable(Some(ext))),
                     "oldstable" => Ok(Self::OldStable(Some(ext))),
+                    "oldoldstable" => Ok(Self::OldOldStable(Some(ext))),
                     _ => Err(ParseError::InvalidSuite),
                 }
             }
@@ -203,6 +215,8 @@ pub enum Codename {
     Trixie(Option<Extension>),
     /// The oldstable suite
     Bookworm(Option<Extension>),
+    /// The oldoldstable suite
+    Bullseye(Option<Extension>),
     /// The experimental suite
     RCBuggy,
 }
@@ -213,6 +227,7 @@ impl WithExtension for Codename {
             Self::Sid | Self::RCBuggy => *self,
             Self::Trixie(_) => Self::Trixie(Some(extension)),
             Self::Bookworm(_) => Self::Bookworm(Some(extension)),
+            Self::Bullseye(_) => Self::Bullseye(Some(extension)),
             Self::Forky(_) => Self::Forky(Some(extension)),
         }
     }
@@ -222,6 +237,7 @@ impl WithExtension for Codename {
             Self::Sid | Self::RCBuggy => *self,
             Self::Trixie(_) => Self::Trixie(None),
             Self::Bookworm(_) => Self::Bookworm(None),
+            Self::Bullseye(_) => Self::Bullseye(None),
             Self::Forky(_) => Self::Forky(None),
         }
     }
@@ -233,10 +249,12 @@ impl Display for Codename {
             Self::Sid => write!(f, "sid"),
             Self::Trixie(None) => write!(f, "trixie"),
             Self::Bookworm(None) => write!(f, "bookworm"),
+            Self::Bullseye(None) => write!(f, "bullseye"),
             Self::Forky(None) => write!(f, "forky"),
             Self::RCBuggy => write!(f, "rc-buggy"),
             Self::Trixie(Some(ext)) => write!(f, "trixie-{ext}"),
             Self::Bookworm(Some(ext)) => write!(f, "bookworm-{ext}"),
+            Self::Bullseye(Some(ext)) => write!(f, "bullseye-{ext}"),
             Self::Forky(Some(ext)) => write!(f, "forky-{ext}"),
         }
     }
@@ -250,6 +268,7 @@ impl TryFrom<&str> for Codename {
             "sid" => Ok(Self::Sid),
             "trixie" => Ok(Self::Trixie(None)),
             "bookworm" => Ok(Self::Bookworm(None)),
+            "bullseye" => Ok(Self::Bullseye(None)),
             "forky" => Ok(Self::Forky(None)),
             "rc-buggy" => Ok(Self::RCBuggy),
             _ => {
@@ -258,6 +277,7 @@ impl TryFrom<&str> for Codename {
                 match s.0 {
                     "trixie" => Ok(Self::Trixie(Some(ext))),
                     "bookworm" => Ok(Self::Bookworm(Some(ext))),
+                    "bullseye" => Ok(Self::Bullseye(Some(ext))),
                     "forky" => Ok(Self::Forky(Some(ext))),
                     _ => Err(ParseError::InvalidCodename),
                 }
@@ -281,6 +301,7 @@ impl From<Suite> for Codename {
             Suite::Testing(ext) => Self::Forky(ext),
             Suite::Stable(ext) => Self::Trixie(ext),
             Suite::OldStable(ext) => Self::Bookworm(ext),
+            Suite::OldOldStable(ext) => Self::Bullseye(ext),
             Suite::Experimental => Self::RCBuggy,
         }
     }
@@ -293,6 +314,7 @@ impl From<Codename> for Suite {
             Codename::Forky(ext) => Self::Testing(ext),
             Codename::Trixie(ext) => Self::Stable(ext),
             Codename::Bookworm(ext) => Self::OldStable(ext),
+            Codename::Bullseye(ext) => Self::OldOldStable(ext),
             Codename::RCBuggy => Self::Experimental,
         }
     }
@@ -336,6 +358,8 @@ impl SuiteOrCodename {
     pub const STABLE: Self = Self::Suite(Suite::Stable(None));
     /// Oldstable
     pub const OLDSTABLE: Self = Self::Suite(Suite::OldStable(None));
+    /// Oldoldstable
+    pub const OLDOLDSTABLE: Self = Self::Suite(Suite::OldOldStable(None));
     /// Experimental
     pub const EXPERIMENTAL: Self = Self::Suite(Suite::Experimental);
     /// Stable proposed-updates
@@ -344,6 +368,10 @@ impl SuiteOrCodename {
     pub const OLDSTABLE_PU: Self = Self::Suite(Suite::OldStable(Some(Extension::ProposedUpdates)));
     /// Stable backports
     pub const STABLE_BACKPORTS: Self = Self::Suite(Suite::Stable(Some(Extension::Backports)));
+    /// Stable security
+    pub const STABLE_SECURITY: Self = Self::Suite(Suite::Stable(Some(Extension::Security)));
+    /// Oldstable security
+    pub const OLDSTABLE_SECURITY: Self = Self::Suite(Suite::OldStable(Some(Extension::Security)));
 }
 
 impl PartialEq for SuiteOrCodename {
@@ -518,7 +546,7 @@ impl FromStr for MultiArch {
 }
 
 /// Debian archive components
-#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Hash)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Eq, PartialEq, Hash)]
 #[serde(rename_all = "lowercase")]
 pub enum Component {
     /// The `main` archive component
@@ -685,6 +713,35 @@ mod test {
         );
     }
 
+    #[test]
+    fn suite_or_codename_round_trip() {
+        for name in [
+            "oldoldstable",
+            "bullseye",
+            "bookworm-security",
+            "oldstable-updates",
+            "bookworm-backports-sloppy",
+            "oldstable-backports-sloppy",
+            "trixie-proposed-updates",
+            "proposed-updates",
+        ] {
+            assert_eq!(
+                SuiteOrCodename::try_from(name).unwrap().to_string(),
+                name,
+                "{name} does not round-trip"
+            );
+        }
+        assert_eq!(
+            SuiteOrCodename::try_from("bookworm-security").unwrap(),
+            SuiteOrCodename::Suite(Suite::OldStable(Some(Extension::Security)))
+        );
+        assert_eq!(
+            SuiteOrCodename::try_from("oldoldstable").unwrap(),
+            SuiteOrCodename::Codename(Codename::Bullseye(None))
+        );
+        assert!(SuiteOrCodename::try_from("bookworm-sloppy").is_err());
+    }
+
     #[test]
     fn suite_or_codename_eq() {
         assert_eq!(
@@ -0,0 +1,292 @@
+// Copyright 2026 Sebastian Ramacher
+// SPDX-License-Identifier: LGPL-3.0-or-later
+
+//! # Helpers to handle distribution profiles
+//!
+//! A distribution profile describes the mapping between suite names and codenames of a
+//! distribution. The mapping for Debian is built into [Suite] and [Codename] and is available as
+//! [DistributionProfile::debian]. Profiles for derivatives or other archives can be built from
+//! their `Release` files or read from a configuration file:
+//!
+//! ```yaml
+//! name: ramacher.at
+//! suites:
+//!   - suite: unstable
+//!     codename: sid
+//! ```
+
+use std::io::{self, BufRead};
+
+use serde::{Deserialize, Serialize};
+
+use crate::archive::{Codename, Extension, Suite};
+
+/// Mapping of a suite name to a codename
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SuiteMapping {
+    /// Suite name, e.g., `unstable`
+    pub suite: String,
+    /// Codename, e.g., `sid`
+    pub codename: String,
+}
+
+/// A resolved suite name and codename
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SuiteAndCodename<'a> {
+    /// The suite name
+    pub suite: &'a str,
+    /// The codename
+    pub codename: &'a str,
+    /// The extension, e.g., `backports`
+    pub extension: Option<Extension>,
+}
+
+/// A distribution profile
+///
+/// Lookups first consider exact matches. Otherwise, names of the form `$base-$extension` are
+/// resolved by looking up `$base` and keeping the [Extension].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DistributionProfile {
+    /// Name of the distribution
+    pub name: String,
+    /// Known suites and their codenames
+    #[serde(default)]
+    pub suites: Vec<SuiteMapping>,
+}
+
+/// Fields of a `Release` file that are relevant for a profile
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ReleaseNames {
+    suite: String,
+    codename: String,
+}
+
+impl DistributionProfile {
+    /// Create a new empty profile
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            suites: Vec::new(),
+        }
+    }
+
+    /// The built-in profile for Debian based on [Suite] and [Codename]
+    pub fn debian() -> Self {
+        let mut profile = Self::new("Debian");
+        for suite in [
+            Suite::Unstable,
+            Suite::Testing(None),
+            Suite::Stable(None),
+            Suite::OldStable(None),
+            Suite::OldOldStable(None),
+            Suite::Experimental,
+        ] {
+            profile.add_suite(suite.to_string(), Codename::from(suite).to_string());
+        }
+        profile
+    }
+
+    /// Add a mapping from a suite name to a codename
+    ///
+    /// Existing mappings for the suite or the codename are replaced.
+    pub fn add_suite(
+        &mut self,
+        suite: impl Into<String>,
+        codename: impl Into<String>,
+    ) -> &mut Self {
+        let suite = suite.into();
+        let codename = codename.into();
+        self.suites
+            .retain(|mapping| mapping.suite != suite && mapping.codename != codename);
+        self.suites.push(SuiteMapping { suite, codename });
+        self
+    }
+
+    /// Add the suite and codename from a `Release` file
+    pub fn add_release(
+        &mut self,
+        reader: impl BufRead,
+    ) -> std::result::Result<&mut Self, rfc822_like::de::Error> {
+        let names: ReleaseNames = rfc822_like::from_reader(reader)?;
+        Ok(self.add_suite(names.suite, names.codename))
+    }
+
+    /// Build a profile from a list of `Release` files
+    pub fn from_releases<R>(
+        name: impl Into<String>,
+        readers: impl IntoIterator<Item = R>,
+    ) -> std::result::Result<Self, rfc822_like::de::Error>
+    where
+        R: BufRead,
+    {
+        let mut profile = Self::new(name);
+        for reader in readers {
+            profile.add_release(reader)?;
+        }
+        Ok(profile)
+    }
+
+    fn lookup<'a>(
+        &'a self,
+        name: &str,
+        matches: impl Fn(&'a SuiteMapping) -> &'a str,
+    ) -> Option<SuiteAndCodename<'a>> {
+        let find = |name: &str| self.suites.iter().find(|mapping| matches(mapping) == name);
+
+        if let Some(mapping) = find(name) {
+            return Some(SuiteAndCodename {
+                suite: &mapping.suite,
+                codename: &mapping.codename,
+                extension: None,
+            });
+        }
+
+        // codenames of derivatives may contain dashes, so check all possible splits
+        name.match_indices('-').find_map(|(idx, _)| {
+            let extension = Extension::try_from(&name[idx + 1..]).ok()?;
+            find(&name[..idx]).map(|mapping| SuiteAndCodename {
+                suite: &mapping.suite,
+                codename: &mapping.codename,
+                extension: Some(extension),
+            })
+        })
+    }
+
+    /// Resolve a suite name
+    pub fn resolve_suite(&self, suite: &str) -> Option<SuiteAndCodename<'_>> {
+        self.lookup(suite, |mapping| &mapping.suite)
+    }
+
+    /// Resolve a codename
+    pub fn resolve_codename(&self, codename: &str) -> Option<SuiteAndCodename<'_>> {
+        self.lookup(codename, |mapping| &mapping.codename)
+    }
+
+    /// Resolve either a suite name or a codename
+    pub fn resolve(&self, suite_or_codename: &str) -> Option<SuiteAndCodename<'_>> {
+        self.resolve_suite(suite_or_codename)
+            .or_else(|| self.resolve_codename(suite_or_codename))
+    }
+
+    /// Get the codename for a suite name
+    pub fn codename_for_suite(&self, suite: &str) -> Option<String> {
+        self.resolve_suite(suite)
+            .map(|resolved| resolved.codename())
+    }
+
+    /// Get the suite name for a codename
+    pub fn suite_for_codename(&self, codename: &str) -> Option<String> {
+        self.resolve_codename(codename)
+            .map(|resolved| resolved.suite())
+    }
+}
+
+impl Default for DistributionProfile {
+    fn default() -> Self {
+        Self::debian()
+    }
+}
+
+impl SuiteAndCodename<'_> {
+    fn with_extension(&self, base: &str) -> String {
+        match self.extension {
+            Some(extension) => format!("{base}-{extension}"),
+            None => base.to_string(),
+        }
+    }
+
+    /// The full suite name including the extension
+    pub fn suite(&self) -> String {
+        self.with_extension(self.suite)
+    }
+
+    /// The full codename including the extension
+    pub fn codename(&self) -> String {
+        self.with_extension(self.codename)
+    }
+}
+
+/// Result type
+pub type Result<T> = serde_yaml::Result<T>;
+
+/// Read a profile from a reader
+pub fn from_reader(reader: impl io::Read) -> Result<DistributionProfile> {
+    serde_yaml::from_reader(reader)
+}
+
+/// Read a profile from a string
+pub fn from_str(data: &str) -> Result<DistributionProfile> {
+    serde_yaml::from_str(data)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn debian() {
+        let profile = DistributionProfile::debian();
+        assert_eq!(
+            profile.codename_for_suite("unstable"),
+            Some("sid".to_string())
+        );
+        assert_eq!(
+            profile.codename_for_suite("stable-backports"),
+            Some(Codename::from(Suite::Stable(Some(Extension::Backports))).to_string())
+        );
+        assert_eq!(
+            profile.suite_for_codename(&Codename::from(Suite::OldStable(None)).to_string()),
+            Some("oldstable".to_string())
+        );
+        assert!(profile.codename_for_suite("stable-foo").is_none());
+        assert!(profile.codename_for_suite("kali-rolling").is_none());
+    }
+
+    #[test]
+    fn derivative() {
+        let profile = from_str(
+            r"---
+name: Kali
+suites:
+  - suite: kali-rolling
+    codename: kali-rolling
+  - suite: kali-last-snapshot
+    codename: kali-2026.3
+",
+        )
+        .unwrap();
+
+        assert_eq!(
+            profile.resolve("kali-rolling"),
+            Some(SuiteAndCodename {
+                suite: "kali-rolling",
+                codename: "kali-rolling",
+                extension: None
+            })
+        );
+        assert_eq!(
+            profile.suite_for_codename("kali-2026.3-security"),
+            Some("kali-last-snapshot-security".to_string())
+        );
+        assert!(profile.resolve("unstable").is_none());
+    }
+
+    #[test]
+    fn add_suite_replaces() {
+        let mut profile = DistributionProfile::new("Test");
+        profile.add_suite("testing", "trixie");
+        profile.add_suite("testing", "forky");
+        profile.add_suite("stable", "trixie");
+
+        assert_eq!(profile.suites.len(), 2);
+        assert_eq!(
+            profile.codename_for_suite("testing"),
+            Some("forky".to_string())
+        );
+        assert_eq!(
+            profile.suite_for_codename("trixie"),
+            Some("stable".to_string())
+        );
+    }
+}
@@ -6,14 +6,25 @@
 //! This module provides helpers to deserialize [excuses.yaml](https://release.debian.org/britney/excuses.yaml)
 //! with [serde]. Note however, that this module only handles a biased selection of fields.
 
-use std::{collections::HashMap, fmt::Formatter, io};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{Display, Formatter},
+    io::{self, BufRead},
+    str::FromStr,
+    vec,
+};
 
 use chrono::{DateTime, Utc};
-use serde::{Deserialize, Deserializer, de};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de, ser::SerializeSeq};
 use smallvec::SmallVec;
+use thiserror::Error;
 
 use crate::{
-    architectures::Architecture, archive::Component, package::PackageName, utils::DateTimeVisitor,
+    architectures::Architecture,
+    archive::Component,
+    maintainer::Maintainer,
+    package::{PackageError, PackageName},
+    utils::{DateTimeVisitor, TryFromStrVisitor},
     version::PackageVersion,
 };
 
@@ -25,6 +36,31 @@ where
     deserializer.deserialize_str(DateTimeVisitor("%Y-%m-%d %H:%M:%S%.f%:z"))
 }
 
+/// Serialize a `DateTime<Utc>` in the format used by britney
+fn serialize_datetime<S>(
+    datetime: &DateTime<Utc>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.collect_str(&datetime.format("%Y-%m-%d %H:%M:%S%.6f%:z"))
+}
+
+/// Serialize an optional version as `PackageVersion` or '-'
+fn serialize_version<S>(
+    version: &Option<PackageVersion>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match version {
+        Some(version) => version.serialize(serializer),
+        None => serializer.serialize_str("-"),
+    }
+}
+
 /// Deserialize a version or '-' as `PackageVersion` or `None`
 fn deserialize_version<'de, D>(
     deserializer: D,
@@ -60,11 +96,14 @@ where
 }
 
 /// The excuses.
-#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct Excuses {
     /// Date of the run that produced `excuses.yaml`
-    #[serde(deserialize_with = "deserialize_datetime")]
+    #[serde(
+        deserialize_with = "deserialize_datetime",
+        serialize_with = "serialize_datetime"
+    )]
     pub generated_date: DateTime<Utc>,
     /// All excuse items
     ///
@@ -73,7 +112,7 @@ pub struct Excuses {
 }
 
 /// A policy's verdict
-#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub enum Verdict {
     /// Policy passed
     #[serde(rename = "PASS")]
@@ -102,8 +141,29 @@ pub enum Verdict {
     RejectedWaitingForAnotherItem,
 }
 
+impl AsRef<str> for Verdict {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Pass => "PASS",
+            Self::PassHinted => "PASS_HINTED",
+            Self::RejectedNeedsApproval => "REJECTED_NEEDS_APPROVAL",
+            Self::RejectedPermanently => "REJECTED_PERMANENTLY",
+            Self::RejectedTemporarily => "REJECTED_TEMPORARILY",
+            Self::RejectedCannotDetermineIfPermanent => "REJECTED_CANNOT_DETERMINE_IF_PERMANENT",
+            Self::RejectedBlockedByAnotherItem => "REJECTED_BLOCKED_BY_ANOTHER_ITEM",
+            Self::RejectedWaitingForAnotherItem => "REJECTED_WAITING_FOR_ANOTHER_ITEM",
+        }
+    }
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_ref())
+    }
+}
+
 /// Age policy info
-#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct AgeInfo {
     /// The required age
@@ -115,7 +175,7 @@ pub struct AgeInfo {
 }
 
 /// Catch-all policy info
-#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct UnspecfiedPolicyInfo {
     /// The verdict
@@ -123,7 +183,7 @@ pub struct UnspecfiedPolicyInfo {
 }
 
 /// Built-on-buildd policy info
-#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct BuiltOnBuildd {
     /// The signers for each architecture
@@ -132,32 +192,474 @@ pub struct BuiltOnBuildd {
     pub verdict: Verdict,
 }
 
+/// Status of an autopkgtest run
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum AutopkgtestStatus {
+    /// Test passed
+    #[serde(rename = "PASS")]
+    Pass,
+    /// Test passed, but with warnings or skipped tests
+    #[serde(rename = "NEUTRAL")]
+    Neutral,
+    /// Test failed
+    #[serde(rename = "FAIL")]
+    Fail,
+    /// Test failed, but also failed before
+    #[serde(rename = "ALWAYSFAIL")]
+    AlwaysFail,
+    /// Test failed and passed before
+    #[serde(rename = "REGRESSION")]
+    Regression,
+    /// Test failed, but the failure is ignored
+    #[serde(rename = "IGNORE-FAIL")]
+    IgnoreFail,
+    /// Test is still running
+    #[serde(rename = "RUNNING")]
+    Running,
+    /// Test is still running, but failed before
+    #[serde(rename = "RUNNING-ALWAYSFAIL")]
+    RunningAlwaysFail,
+    /// Test is still running against the target suite
+    #[serde(rename = "RUNNING-REFERENCE")]
+    RunningReference,
+    /// Test is still running, but a failure will be ignored
+    #[serde(rename = "RUNNING-IGNORE")]
+    RunningIgnore,
+    /// Unknown status
+    #[serde(other)]
+    Unknown,
+}
+
+impl AsRef<str> for AutopkgtestStatus {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Pass => "PASS",
+            Self::Neutral => "NEUTRAL",
+            Self::Fail => "FAIL",
+            Self::AlwaysFail => "ALWAYSFAIL",
+            Self::Regression => "REGRESSION",
+            Self::IgnoreFail => "IGNORE-FAIL",
+            Self::Running => "RUNNING",
+            Self::RunningAlwaysFail => "RUNNING-ALWAYSFAIL",
+            Self::RunningReference => "RUNNING-REFERENCE",
+            Self::RunningIgnore => "RUNNING-IGNORE",
+            Self::Unknown => "UNKNOWN",
+        }
+    }
+}
+
+impl Display for AutopkgtestStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_ref())
+    }
+}
+
+/// Result of an autopkgtest run on one architecture
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AutopkgtestResult {
+    /// The status
+    pub status: AutopkgtestStatus,
+    /// URL of the log
+    pub log_url: Option<String>,
+    /// URL of the package's test history
+    pub history_url: Option<String>,
+    /// URL of the test artifacts
+    pub artifacts_url: Option<String>,
+    /// URL to retry the test
+    pub retry_url: Option<String>,
+}
+
+impl<'de> Deserialize<'de> for AutopkgtestResult {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct Visitor;
+
+        impl<'de> de::Visitor<'de> for Visitor {
+            type Value = AutopkgtestResult;
+
+            fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+                write!(formatter, "a list of an autopkgtest status and URLs")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> std::result::Result<Self::Value, A::Error>
+            where
+                A: de::SeqAccess<'de>,
+            {
+                let status = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+                // britney may omit trailing URLs
+                let mut next_url = || -> std::result::Result<Option<String>, A::Error> {
+                    Ok(seq.next_element::<Option<String>>()?.flatten())
+                };
+                let log_url = next_url()?;
+                let history_url = next_url()?;
+                let artifacts_url = next_url()?;
+                let retry_url = next_url()?;
+                while seq.next_element::<de::IgnoredAny>()?.is_some() {}
+
+                Ok(AutopkgtestResult {
+                    status,
+                    log_url,
+                    history_url,
+                    artifacts_url,
+                    retry_url,
+                })
+            }
+        }
+
+        deserializer.deserialize_seq(Visitor)
+    }
+}
+
+impl Serialize for AutopkgtestResult {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut seq = serializer.serialize_seq(Some(5))?;
+        seq.serialize_element(&self.status)?;
+        seq.serialize_element(&self.log_url)?;
+        seq.serialize_element(&self.history_url)?;
+        seq.serialize_element(&self.artifacts_url)?;
+        seq.serialize_element(&self.retry_url)?;
+        seq.end()
+    }
+}
+
+/// Autopkgtest policy info
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct AutopkgtestInfo {
+    /// The verdict
+    pub verdict: Verdict,
+    /// Results per tested package (`$package/$version`) and architecture
+    #[serde(flatten)]
+    pub results: HashMap<String, HashMap<Architecture, AutopkgtestResult>>,
+}
+
+impl AutopkgtestInfo {
+    /// Iterate over the results of the tests of the given package
+    ///
+    /// Returns the full test name (`$package/$version`) together with the results per architecture.
+    pub fn results_for_package<'a>(
+        &'a self,
+        package: &'a str,
+    ) -> impl Iterator<Item = (&'a str, &'a HashMap<Architecture, AutopkgtestResult>)> {
+        self.results.iter().filter_map(move |(test, results)| {
+            (test
+                .split_once('/')
+                .map_or(test.as_str(), |(tested, _)| tested)
+                == package)
+                .then_some((test.as_str(), results))
+        })
+    }
+}
+
+/// Deserialize a list of bug numbers that are either given as strings or numbers
+fn deserialize_bugs<'de, D>(deserializer: D) -> std::result::Result<Vec<u32>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Bug {
+        Number(u32),
+        String(String),
+    }
+
+    Vec::<Bug>::deserialize(deserializer)?
+        .into_iter()
+        .map(|bug| match bug {
+            Bug::Number(bug) => Ok(bug),
+            Bug::String(bug) => bug
+                .parse()
+                .map_err(|_| de::Error::invalid_value(de::Unexpected::Str(&bug), &"a bug number")),
+        })
+        .collect()
+}
+
+/// Serialize a list of bug numbers as strings like britney does
+fn serialize_bugs<S>(bugs: &[u32], serializer: S) -> std::result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.collect_seq(bugs.iter().map(ToString::to_string))
+}
+
+/// RC bugs policy info
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct RcBugsInfo {
+    /// RC bugs only affecting the source suite
+    #[serde(
+        default,
+        deserialize_with = "deserialize_bugs",
+        serialize_with = "serialize_bugs"
+    )]
+    pub unique_source_bugs: Vec<u32>,
+    /// RC bugs only affecting the target suite
+    #[serde(
+        default,
+        deserialize_with = "deserialize_bugs",
+        serialize_with = "serialize_bugs"
+    )]
+    pub unique_target_bugs: Vec<u32>,
+    /// RC bugs affecting both suites
+    #[serde(
+        default,
+        deserialize_with = "deserialize_bugs",
+        serialize_with = "serialize_bugs"
+    )]
+    pub shared_bugs: Vec<u32>,
+    /// The verdict
+    pub verdict: Verdict,
+}
+
+/// Result of piuparts tests
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum PiupartsResult {
+    /// Tests passed
+    Pass,
+    /// Tests failed, but passed in the target suite
+    Regression,
+    /// Tests failed
+    Failed,
+    /// Package cannot be tested
+    CannotBeTested,
+    /// Tests have not been run yet
+    WaitingForTestResults,
+    /// Unknown result
+    #[serde(other)]
+    Unknown,
+}
+
+/// Piuparts policy info
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PiupartsInfo {
+    /// The test result
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub test_results: Option<PiupartsResult>,
+    /// URL of the piuparts results
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub piuparts_test_url: Option<String>,
+    /// The verdict
+    pub verdict: Verdict,
+}
+
+/// Depends policy info
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct DependsInfo {
+    /// Unsatisfiable dependencies per architecture
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub unsatisfiable_dependencies: HashMap<Architecture, Vec<String>>,
+    /// The verdict
+    pub verdict: Verdict,
+}
+
+/// Build-depends policy info
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct BuildDependsInfo {
+    /// Unsatisfiable `Build-Depends` and `Build-Depends-Arch` per architecture
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub unsatisfiable_arch_build_depends: HashMap<Architecture, Vec<String>>,
+    /// Unsatisfiable `Build-Depends-Indep` per architecture
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub unsatisfiable_indep_build_depends: HashMap<Architecture, Vec<String>>,
+    /// The verdict
+    pub verdict: Verdict,
+}
+
+/// Built-using policy info
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct BuiltUsingInfo {
+    /// The verdict
+    pub verdict: Verdict,
+}
+
+/// Hint referenced by the block policy
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct BlockHintInfo {
+    /// The hint type, e.g., `block` or `unblock`
+    #[serde(rename = "type")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint_type: Option<String>,
+    /// The user or reason that added the hint, e.g., `freeze`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub by: Option<String>,
+}
+
+/// Block policy info
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct BlockInfo {
+    /// The verdict
+    pub verdict: Verdict,
+    /// The relevant hints, e.g., `block`, `block-udeb` or `unblock`
+    #[serde(flatten)]
+    pub hints: HashMap<String, BlockHintInfo>,
+}
+
+/// Binaries broken by implicit dependencies
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ImplicitDependencies {
+    /// The broken binaries
+    #[serde(default)]
+    pub broken_binaries: Vec<String>,
+}
+
+/// Implicit-deps policy info
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ImplicitDepsInfo {
+    /// Binaries broken by implicit dependencies
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub implicit_deps: Option<ImplicitDependencies>,
+    /// The verdict
+    pub verdict: Verdict,
+}
+
 /// Collected policy infos
-#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct PolicyInfo {
     /// The age policy
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub age: Option<AgeInfo>,
     /// The buildt-on-buildd policy
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub builtonbuildd: Option<BuiltOnBuildd>,
-    /// The autopkgtest porlicy
-    pub autopkgtest: Option<UnspecfiedPolicyInfo>,
+    /// The autopkgtest policy
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub autopkgtest: Option<AutopkgtestInfo>,
+    /// The RC bugs policy
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rc_bugs: Option<RcBugsInfo>,
+    /// The piuparts policy
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub piuparts: Option<PiupartsInfo>,
+    /// The depends policy
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub depends: Option<DependsInfo>,
+    /// The build-depends policy
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub build_depends: Option<BuildDependsInfo>,
+    /// The built-using policy
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub built_using: Option<BuiltUsingInfo>,
+    /// The block policy
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block: Option<BlockInfo>,
+    /// The implicit-deps policy
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub implicit_deps: Option<ImplicitDepsInfo>,
     /// All remaining policies
     #[serde(flatten)]
     pub extras: HashMap<String, UnspecfiedPolicyInfo>,
-    /*
-        autopkgtest: Option<UnspecfiedPolicyInfo>,
-        block: Option<UnspecfiedPolicyInfo>,
-        build_depends: Option<UnspecfiedPolicyInfo>,
-        built_using:  Option<UnspecfiedPolicyInfo>,
-        depends: Option<UnspecfiedPolicyInfo>,
-        piuparts: Option<UnspecfiedPolicyInfo>,
-        rc_bugs: Option<UnspecfiedPolicyInfo>,
-    */
+}
+
+impl PolicyInfo {
+    /// Only keep the policies for which the predicate returns `true`
+    pub fn retain(&mut self, mut f: impl FnMut(&str, Verdict) -> bool) {
+        fn retain_policy<T>(
+            policy: &mut Option<T>,
+            name: &str,
+            verdict: impl Fn(&T) -> Verdict,
+            f: &mut impl FnMut(&str, Verdict) -> bool,
+        ) {
+            if policy.as_ref().is_some_and(|info| !f(name, verdict(info))) {
+                *policy = None;
+            }
+        }
+
+        retain_policy(&mut self.age, "age", |info| info.verdict, &mut f);
+        retain_policy(
+            &mut self.builtonbuildd,
+            "builtonbuildd",
+            |info| info.verdict,
+            &mut f,
+        );
+        retain_policy(
+            &mut self.autopkgtest,
+            "autopkgtest",
+            |info| info.verdict,
+            &mut f,
+        );
+        retain_policy(&mut self.rc_bugs, "rc-bugs", |info| info.verdict, &mut f);
+        retain_policy(&mut self.piuparts, "piuparts", |info| info.verdict, &mut f);
+        retain_policy(&mut self.depends, "depends", |info| info.verdict, &mut f);
+        retain_policy(
+            &mut self.build_depends,
+            "build-depends",
+            |info| info.verdict,
+            &mut f,
+        );
+        retain_policy(
+            &mut self.built_using,
+            "built-using",
+            |info| info.verdict,
+            &mut f,
+        );
+        retain_policy(&mut self.block, "block", |info| info.verdict, &mut f);
+        retain_policy(
+            &mut self.implicit_deps,
+            "implicit-deps",
+            |info| info.verdict,
+            &mut f,
+        );
+        self.extras.retain(|name, info| f(name, info.verdict));
+    }
+
+    /// Iterate over the names and verdicts of all policies
+    pub fn verdicts(&self) -> impl Iterator<Item = (&str, Verdict)> {
+        [
+            ("age", self.age.as_ref().map(|info| info.verdict)),
+            (
+                "builtonbuildd",
+                self.builtonbuildd.as_ref().map(|info| info.verdict),
+            ),
+            (
+                "autopkgtest",
+                self.autopkgtest.as_ref().map(|info| info.verdict),
+            ),
+            ("rc-bugs", self.rc_bugs.as_ref().map(|info| info.verdict)),
+            ("piuparts", self.piuparts.as_ref().map(|info| info.verdict)),
+            ("depends", self.depends.as_ref().map(|info| info.verdict)),
+            (
+                "build-depends",
+                self.build_depends.as_ref().map(|info| info.verdict),
+            ),
+            (
+                "built-using",
+                self.built_using.as_ref().map(|info| info.verdict),
+            ),
+            ("block", self.block.as_ref().map(|info| info.verdict)),
+            (
+                "implicit-deps",
+                self.implicit_deps.as_ref().map(|info| info.verdict),
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(name, verdict)| verdict.map(|verdict| (name, verdict)))
+        .chain(
+            self.extras
+                .iter()
+                .map(|(name, info)| (name.as_str(), info.verdict)),
+        )
+    }
 }
 
 /// List of missing builds
-#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct MissingBuilds {
     /// Architectures where builds are missing
@@ -165,36 +667,303 @@ pub struct MissingBuilds {
     pub on_architectures: SmallVec<[Architecture; 16]>,
 }
 
+/// Migration item errors
+#[derive(Clone, Debug, Error)]
+pub enum MigrationItemError {
+    #[error("invalid package: {0}")]
+    /// The source package name is invalid
+    InvalidPackage(#[from] PackageError),
+    #[error("invalid version: {0}")]
+    /// The version is invalid
+    InvalidVersion(String),
+    #[error("invalid architecture: {0}")]
+    /// The architecture is invalid or unknown
+    InvalidArchitecture(String),
+    #[error("invalid suite: {0}")]
+    /// The suite suffix is invalid
+    InvalidSuite(String),
+    #[error("too many components")]
+    /// The item consists of too many `/`-separated components
+    TooManyComponents,
+}
+
+/// Suite of a migration item if it is not the default source suite
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MigrationSuite {
+    /// The item is from `proposed-updates`
+    ProposedUpdates,
+    /// The item is from `testing-proposed-updates`
+    TestingProposedUpdates,
+}
+
+impl AsRef<str> for MigrationSuite {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::ProposedUpdates => "pu",
+            Self::TestingProposedUpdates => "tpu",
+        }
+    }
+}
+
+impl Display for MigrationSuite {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_ref())
+    }
+}
+
+impl TryFrom<&str> for MigrationSuite {
+    type Error = MigrationItemError;
+
+    fn try_from(value: &str) -> std::result::Result<Self, Self::Error> {
+        match value {
+            "pu" => Ok(Self::ProposedUpdates),
+            "tpu" => Ok(Self::TestingProposedUpdates),
+            _ => Err(MigrationItemError::InvalidSuite(value.to_owned())),
+        }
+    }
+}
+
+/// A migration item as named by britney
+///
+/// Item names have the form `[-]source[/version][/architecture][_suite]`, e.g., `zathura` for a
+/// source upgrade, `-zathura` for a removal, `zathura/amd64` for a binNMU, or `zathura_tpu` for an
+/// item from `testing-proposed-updates`. When parsing, the suite is also accepted after the source
+/// package as used in hints, e.g., `zathura_tpu/0.5.11-1`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MigrationItem {
+    /// The item removes the package
+    pub removal: bool,
+    /// The source package
+    pub source: PackageName,
+    /// The version, if specified
+    pub version: Option<PackageVersion>,
+    /// The architecture for binary-only items, e.g., binNMUs
+    pub architecture: Option<Architecture>,
+    /// The suite if the item is not from the default source suite
+    pub suite: Option<MigrationSuite>,
+}
+
+impl MigrationItem {
+    /// Create an item for a source package
+    pub fn new(source: PackageName) -> Self {
+        Self {
+            removal: false,
+            source,
+            version: None,
+            architecture: None,
+            suite: None,
+        }
+    }
+
+    /// Specify the version
+    pub fn with_version(mut self, version: PackageVersion) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    /// Specify the architecture of binary-only items
+    pub fn with_architecture(mut self, architecture: Architecture) -> Self {
+        self.architecture = Some(architecture);
+        self
+    }
+
+    /// Specify the suite
+    pub fn with_suite(mut self, suite: MigrationSuite) -> Self {
+        self.suite = Some(suite);
+        self
+    }
+
+    /// Display the item as used in hints
+    ///
+    /// Hints place the suite after the source package, e.g., `zathura_tpu/0.5.11-1`.
+    pub fn hint_name(&self) -> impl Display + '_ {
+        HintName(self)
+    }
+}
+
+/// Helper to display a migration item as used in hints
+struct HintName<'a>(&'a MigrationItem);
+
+impl Display for HintName<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let item = self.0;
+        if item.removal {
+            write!(f, "-")?;
+        }
+        write!(f, "{}", item.source)?;
+        if let Some(suite) = item.suite {
+            write!(f, "_{suite}")?;
+        }
+        if let Some(version) = &item.version {
+            write!(f, "/{version}")?;
+        }
+        if let Some(architecture) = item.architecture {
+            write!(f, "/{architecture}")?;
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<&str> for MigrationItem {
+    type Error = MigrationItemError;
+
+    fn try_from(value: &str) -> std::result::Result<Self, Self::Error> {
+        let (removal, value) = value
+            .strip_prefix('-')
+            .map_or((false, value), |value| (true, value));
+
+        // neither package names, versions nor architectures contain `_`, so the suite can be split
+        // off any component: item names append it to the last one and hints to the source package
+        fn strip_suite<'a>(
+            component: &'a str,
+            suite: &mut Option<MigrationSuite>,
+        ) -> std::result::Result<&'a str, MigrationItemError> {
+            match component.split_once('_') {
+                Some((component, value)) if suite.is_none() => {
+                    *suite = Some(MigrationSuite::try_from(value)?);
+                    Ok(component)
+                }
+                Some(_) => Err(MigrationItemError::InvalidSuite(component.to_owned())),
+                None => Ok(component),
+            }
+        }
+
+        let mut suite = None;
+        let mut components = value.split('/');
+        // split always returns at least one element
+        let source = PackageName::try_from(strip_suite(components.next().unwrap(), &mut suite)?)?;
+        let mut version = None;
+        let mut architecture = None;
+        for component in components {
+            if architecture.is_some() {
+                return Err(MigrationItemError::TooManyComponents);
+            }
+            let component = strip_suite(component, &mut suite)?;
+            // versions start with a digit, architectures never do
+            if component.starts_with(|c: char| c.is_ascii_digit()) && version.is_none() {
+                version = Some(
+                    PackageVersion::try_from(component)
+                        .map_err(|_| MigrationItemError::InvalidVersion(component.to_owned()))?,
+                );
+            } else {
+                architecture =
+                    Some(Architecture::try_from(component).map_err(|_| {
+                        MigrationItemError::InvalidArchitecture(component.to_owned())
+                    })?);
+            }
+        }
+
+        Ok(Self {
+            removal,
+            source,
+            version,
+            architecture,
+            suite,
+        })
+    }
+}
+
+impl FromStr for MigrationItem {
+    type Err = MigrationItemError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Self::try_from(s)
+    }
+}
+
+impl Display for MigrationItem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.removal {
+            write!(f, "-")?;
+        }
+        write!(f, "{}", self.source)?;
+        if let Some(version) = &self.version {
+            write!(f, "/{version}")?;
+        }
+        if let Some(architecture) = self.architecture {
+            write!(f, "/{architecture}")?;
+        }
+        if let Some(suite) = self.suite {
+            write!(f, "_{suite}")?;
+        }
+        Ok(())
+    }
+}
+
+impl<'de> Deserialize<'de> for MigrationItem {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(TryFromStrVisitor::new("a migration item"))
+    }
+}
+
+impl Serialize for MigrationItem {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+/// Dependencies of an item on other items
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Dependencies {
+    /// Items that block the migration of this item
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blocked_by: Vec<MigrationItem>,
+    /// Items that need to migrate before or together with this item
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub migrate_after: Vec<MigrationItem>,
+}
+
 /// A source package's excuses
-#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct ExcusesItem {
     /// Maintainer of the package
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub maintainer: Option<String>,
     /// The item is a candidate for migration
     pub is_candidate: bool,
     /// Version in the source suite, i.e., the version to migrate
     ///
     /// If the value is `None`, the package is being removed.
-    #[serde(deserialize_with = "deserialize_version")]
+    #[serde(
+        deserialize_with = "deserialize_version",
+        serialize_with = "serialize_version"
+    )]
     pub new_version: Option<PackageVersion>,
     /// Version in the target suite
     ///
     /// If the value is `None`, the package is not yet available in the target suite.
-    #[serde(deserialize_with = "deserialize_version")]
+    #[serde(
+        deserialize_with = "deserialize_version",
+        serialize_with = "serialize_version"
+    )]
     pub old_version: Option<PackageVersion>,
     /// Migration item name
-    pub item_name: String,
+    pub item_name: MigrationItem,
     /// Source package name
     pub source: PackageName,
     /// Migration is blocked by another package
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub invalidated_by_other_package: Option<bool>,
+    /// Dependencies on other items
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dependencies: Option<Dependencies>,
     /// Component of the source package
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub component: Option<Component>,
     /// Missing builds
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub missing_builds: Option<MissingBuilds>,
     /// Policy info
     #[serde(rename = "policy_info")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub policy_info: Option<PolicyInfo>,
     /// The excuses
     pub excuses: Vec<String>,
@@ -215,22 +984,149 @@ impl ExcusesItem {
 
     /// Get architecture of the binNMU or `None`
     pub fn binnmu_arch(&self) -> Option<Architecture> {
-        self.item_name.split_once('/').map(|(_, arch)| {
-            arch.split_once('_')
-                .map_or(arch, |(arch, _)| arch)
-                .try_into()
-                .unwrap()
-        })
+        self.item_name.architecture
     }
 
     /// Excuses item refers to an item in (stable) proposed-updates
     pub fn is_from_pu(&self) -> bool {
-        self.item_name.ends_with("_pu")
+        self.item_name.suite == Some(MigrationSuite::ProposedUpdates)
     }
 
     /// Excuses item refers to an item in testing-proposed-updates
     pub fn is_from_tpu(&self) -> bool {
-        self.item_name.ends_with("_tpu")
+        self.item_name.suite == Some(MigrationSuite::TestingProposedUpdates)
+    }
+}
+
+impl Excuses {
+    /// Build the graph of dependencies between the items
+    pub fn migration_graph(&self) -> MigrationGraph<'_> {
+        MigrationGraph::new(&self.sources)
+    }
+}
+
+/// Graph of the dependencies between migration items
+///
+/// Edges point from an item to the items listed in its `blocked-by` and `migrate-after`
+/// dependencies. Dependencies on items that are not part of the excuses are ignored.
+#[derive(Debug)]
+pub struct MigrationGraph<'a> {
+    items: &'a [ExcusesItem],
+    index: HashMap<&'a MigrationItem, usize>,
+    dependencies: Vec<Vec<usize>>,
+    reverse_dependencies: Vec<Vec<usize>>,
+}
+
+impl<'a> MigrationGraph<'a> {
+    /// Build the graph from a list of items
+    pub fn new(items: &'a [ExcusesItem]) -> Self {
+        let index: HashMap<_, _> = items
+            .iter()
+            .enumerate()
+            .map(|(idx, item)| (&item.item_name, idx))
+            .collect();
+
+        let mut dependencies = vec![Vec::new(); items.len()];
+        let mut reverse_dependencies = vec![Vec::new(); items.len()];
+        for (idx, item) in items.iter().enumerate() {
+            let Some(item_dependencies) = &item.dependencies else {
+                continue;
+            };
+            for dependency in item_dependencies
+                .blocked_by
+                .iter()
+                .chain(&item_dependencies.migrate_after)
+            {
+                if let Some(&dependency_idx) = index.get(dependency)
+                    && !dependencies[idx].contains(&dependency_idx)
+                {
+                    dependencies[idx].push(dependency_idx);
+                    reverse_dependencies[dependency_idx].push(idx);
+                }
+            }
+        }
+
+        Self {
+            items,
+            index,
+            dependencies,
+            reverse_dependencies,
+        }
+    }
+
+    /// Get an item by its name
+    pub fn item(&self, item_name: &MigrationItem) -> Option<&'a ExcusesItem> {
+        self.index.get(item_name).map(|idx| &self.items[*idx])
+    }
+
+    /// Collect all items reachable from `start` (excluding `start`)
+    fn reachable(&self, start: usize, edges: &[&[Vec<usize>]]) -> Vec<usize> {
+        let mut seen = HashSet::from([start]);
+        let mut stack = vec![start];
+        let mut result = Vec::new();
+        while let Some(idx) = stack.pop() {
+            for next in edges.iter().flat_map(|edges| &edges[idx]) {
+                if seen.insert(*next) {
+                    result.push(*next);
+                    stack.push(*next);
+                }
+            }
+        }
+        result
+    }
+
+    /// Get all items that directly or indirectly block the migration of the given item
+    pub fn blockers(&self, item_name: &MigrationItem) -> Vec<&'a ExcusesItem> {
+        self.index.get(item_name).map_or_else(Vec::new, |idx| {
+            self.reachable(*idx, &[&self.dependencies])
+                .into_iter()
+                .map(|idx| &self.items[idx])
+                .collect()
+        })
+    }
+
+    /// Get the blockers of the given item that are not blocked by any other item
+    ///
+    /// If the blockers only form cycles, all blockers are returned.
+    pub fn root_blockers(&self, item_name: &MigrationItem) -> Vec<&'a ExcusesItem> {
+        let Some(&start) = self.index.get(item_name) else {
+            return Vec::new();
+        };
+
+        let blockers = self.reachable(start, &[&self.dependencies]);
+        let roots: Vec<_> = blockers
+            .iter()
+            .filter(|idx| self.dependencies[**idx].is_empty())
+            .map(|idx| &self.items[*idx])
+            .collect();
+        if roots.is_empty() {
+            // only cycles remain, so every blocker needs attention
+            blockers.into_iter().map(|idx| &self.items[idx]).collect()
+        } else {
+            roots
+        }
+    }
+
+    /// Get all groups of items that are connected by dependencies
+    ///
+    /// Items without any dependencies are not included.
+    pub fn migration_groups(&self) -> Vec<Vec<&'a ExcusesItem>> {
+        let mut seen = HashSet::new();
+        let mut groups = Vec::new();
+        for idx in 0..self.items.len() {
+            if seen.contains(&idx)
+                || (self.dependencies[idx].is_empty() && self.reverse_dependencies[idx].is_empty())
+            {
+                continue;
+            }
+
+            let mut group = vec![idx];
+            group.extend(self.reachable(idx, &[&self.dependencies, &self.reverse_dependencies]));
+            group.sort_unstable();
+            seen.extend(group.iter().copied());
+            groups.push(group.into_iter().map(|idx| &self.items[idx]).collect());
+        }
+        groups
     }
 }
 
@@ -246,3 +1142,673 @@ pub fn from_reader(reader: impl io::Read) -> Result<Excuses> {
 pub fn from_str(data: &str) -> Result<Excuses> {
     serde_yaml::from_str(data)
 }
+
+/// Header of `excuses.yaml`, i.e., everything except the items
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct ExcusesHeader {
+    #[serde(deserialize_with = "deserialize_datetime")]
+    generated_date: DateTime<Utc>,
+}
+
+#[derive(Debug)]
+enum ExcusesReaderState<R> {
+    /// Items are parsed one at a time from the reader
+    Streaming {
+        reader: R,
+        /// First line of the next item
+        next_line: Option<String>,
+    },
+    /// Fallback for layouts that cannot be streamed
+    Buffered(vec::IntoIter<ExcusesItem>),
+}
+
+/// Reader that yields the items of `excuses.yaml` one at a time
+///
+/// britney writes all items as block sequence below the `sources` key. Each item is parsed on its
+/// own as soon as it has been read completely, so only a single item is kept in memory. Other
+/// layouts, e.g., JSON, are supported by parsing the complete input first.
+///
+/// ```
+/// use assorted_debian_utils::excuses::ExcusesReader;
+///
+/// let data = "generated-date: 2026-10-01 10:42:13.123456+00:00\nsources: []\n";
+/// let reader = ExcusesReader::new(data.as_bytes()).unwrap();
+/// assert_eq!(reader.count(), 0);
+/// ```
+#[derive(Debug)]
+pub struct ExcusesReader<R> {
+    generated_date: DateTime<Utc>,
+    state: ExcusesReaderState<R>,
+}
+
+fn io_error(err: io::Error) -> serde_yaml::Error {
+    de::Error::custom(err)
+}
+
+/// Check if a line starts a new top-level key or item
+fn is_top_level(line: &str) -> bool {
+    !line.starts_with([' ', '\t', '\n', '\r', '#'])
+}
+
+impl<R> ExcusesReader<R>
+where
+    R: BufRead,
+{
+    /// Create a new reader and read the header
+    pub fn new(mut reader: R) -> Result<Self> {
+        let mut header = String::new();
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).map_err(io_error)? == 0 {
+                // no items in block layout
+                return Self::buffered(header);
+            }
+
+            if line.trim_end() == "sources:" {
+                header.push_str(&line);
+                break;
+            }
+            if line.starts_with("sources:") || line.starts_with(['{', '[']) {
+                // flow style or JSON
+                header.push_str(&line);
+                reader.read_to_string(&mut header).map_err(io_error)?;
+                return Self::buffered(header);
+            }
+            header.push_str(&line);
+        }
+
+        let mut first_item = String::new();
+        reader.read_line(&mut first_item).map_err(io_error)?;
+        if !first_item.starts_with("- ") {
+            // indented items
+            header.push_str(&first_item);
+            reader.read_to_string(&mut header).map_err(io_error)?;
+            return Self::buffered(header);
+        }
+
+        let header: ExcusesHeader = serde_yaml::from_str(&header)?;
+        Ok(Self {
+            generated_date: header.generated_date,
+            state: ExcusesReaderState::Streaming {
+                reader,
+                next_line: Some(first_item),
+            },
+        })
+    }
+
+    fn buffered(data: String) -> Result<Self> {
+        let excuses = from_str(&data)?;
+        Ok(Self {
+            generated_date: excuses.generated_date,
+            state: ExcusesReaderState::Buffered(excuses.sources.into_iter()),
+        })
+    }
+
+    /// Date of the run that produced `excuses.yaml`
+    pub fn generated_date(&self) -> DateTime<Utc> {
+        self.generated_date
+    }
+
+    fn next_item(reader: &mut R, next_line: &mut Option<String>) -> Result<Option<ExcusesItem>> {
+        let mut item = match next_line.take() {
+            Some(line) => line,
+            None => loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).map_err(io_error)? == 0 {
+                    return Ok(None);
+                }
+                if line.starts_with("- ") {
+                    break line;
+                }
+                if is_top_level(&line) {
+                    // end of the items
+                    return Ok(None);
+                }
+            },
+        };
+
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).map_err(io_error)? == 0 {
+                break;
+            }
+            if line.starts_with("- ") {
+                *next_line = Some(line);
+                break;
+            }
+            if is_top_level(&line) {
+                // end of the items, ignore remaining keys
+                let mut rest = String::new();
+                reader.read_to_string(&mut rest).map_err(io_error)?;
+                break;
+            }
+            item.push_str(&line);
+        }
+
+        let mut items: Vec<ExcusesItem> = serde_yaml::from_str(&item)?;
+        Ok(items.pop())
+    }
+}
+
+impl<R> Iterator for ExcusesReader<R>
+where
+    R: BufRead,
+{
+    type Item = Result<ExcusesItem>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.state {
+            ExcusesReaderState::Streaming { reader, next_line } => {
+                Self::next_item(reader, next_line).transpose()
+            }
+            ExcusesReaderState::Buffered(items) => items.next().map(Ok),
+        }
+    }
+}
+
+/// Index of excuses items by source package and maintainer
+///
+/// Maintainers are indexed by their name, their email address and the complete field ignoring case.
+#[derive(Debug, Default)]
+pub struct ExcusesIndex {
+    items: Vec<ExcusesItem>,
+    by_source: HashMap<PackageName, Vec<usize>>,
+    by_maintainer: HashMap<String, Vec<usize>>,
+}
+
+impl ExcusesIndex {
+    /// Add an item to the index
+    pub fn insert(&mut self, item: ExcusesItem) {
+        let idx = self.items.len();
+        self.by_source
+            .entry(item.source.clone())
+            .or_default()
+            .push(idx);
+        if let Some(maintainer) = &item.maintainer {
+            let parsed = Maintainer::from(maintainer.as_str());
+            let mut keys = vec![
+                maintainer.to_ascii_lowercase(),
+                parsed.name.to_ascii_lowercase(),
+            ];
+            keys.extend(parsed.email.map(|email| email.to_ascii_lowercase()));
+            keys.sort_unstable();
+            keys.dedup();
+            for key in keys {
+                self.by_maintainer.entry(key).or_default().push(idx);
+            }
+        }
+        self.items.push(item);
+    }
+
+    fn lookup<'a>(
+        &'a self,
+        indices: Option<&'a Vec<usize>>,
+    ) -> impl Iterator<Item = &'a ExcusesItem> {
+        indices.into_iter().flatten().map(|idx| &self.items[*idx])
+    }
+
+    /// Get all items of a source package
+    pub fn by_source(&self, source: &str) -> impl Iterator<Item = &ExcusesItem> {
+        self.lookup(self.by_source.get(source))
+    }
+
+    /// Get all items of a maintainer given by name or email address ignoring case
+    pub fn by_maintainer(&self, maintainer: &str) -> impl Iterator<Item = &ExcusesItem> {
+        self.lookup(self.by_maintainer.get(&maintainer.to_ascii_lowercase()))
+    }
+
+    /// Get all items of a source package or a maintainer in the order they were inserted
+    ///
+    /// Both the source package and the maintainer are matched ignoring case.
+    pub fn by_source_or_maintainer(&self, name: &str) -> impl Iterator<Item = &ExcusesItem> {
+        let name = name.to_ascii_lowercase();
+        let mut indices: Vec<_> = self
+            .by_source
+            .get(name.as_str())
+            .into_iter()
+            .chain(self.by_maintainer.get(&name))
+            .flatten()
+            .copied()
+            .collect();
+        indices.sort_unstable();
+        indices.dedup();
+        indices.into_iter().map(|idx| &self.items[idx])
+    }
+
+    /// Get all items
+    pub fn items(&self) -> &[ExcusesItem] {
+        &self.items
+    }
+
+    /// Consume the index and return all items
+    pub fn into_items(self) -> Vec<ExcusesItem> {
+        self.items
+    }
+
+    /// Number of indexed items
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Check if the index is empty
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+impl FromIterator<ExcusesItem> for ExcusesIndex {
+    fn from_iter<T: IntoIterator<Item = ExcusesItem>>(iter: T) -> Self {
+        let mut index = Self::default();
+        for item in iter {
+            index.insert(item);
+        }
+        index
+    }
+}
+
+impl From<Excuses> for ExcusesIndex {
+    fn from(excuses: Excuses) -> Self {
+        excuses.sources.into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TEST_DATA: &str = r"---
+generated-date: 2026-10-01 10:42:13.123456+00:00
+sources:
+- component: main
+  excuses:
+  - 'Migration status for zathura (0.5.10-1 to 0.5.11-1): BLOCKED: Rejected/violates migration policy/introduces a regression'
+  is-candidate: false
+  item-name: zathura
+  maintainer: Sebastian Ramacher
+  migration-policy-verdict: REJECTED_PERMANENTLY
+  new-version: 0.5.11-1
+  old-version: 0.5.10-1
+  policy_info:
+    age:
+      age-requirement: 5
+      current-age: 7
+      verdict: PASS
+    autopkgtest:
+      zathura-pdf-poppler/0.3.3-1:
+        amd64:
+        - REGRESSION
+        - https://ci.debian.net/data/autopkgtest/testing/amd64/z/zathura-pdf-poppler/1234/log.gz
+        - https://ci.debian.net/packages/z/zathura-pdf-poppler/testing/amd64
+        - null
+        - https://ci.debian.net/api/v1/retry/1234
+        arm64:
+        - PASS
+        - https://ci.debian.net/data/autopkgtest/testing/arm64/z/zathura-pdf-poppler/1235/log.gz
+      verdict: REJECTED_PERMANENTLY
+    block:
+      block:
+        by: freeze
+        type: block
+      verdict: REJECTED_NEEDS_APPROVAL
+    build-depends:
+      unsatisfiable-arch-build-depends:
+        i386:
+        - libgirara-dev (>= 0.4.5)
+      verdict: REJECTED_PERMANENTLY
+    builtonbuildd:
+      signed-by:
+        amd64: buildd_amd64-x86-ubc-01@buildd.debian.org
+      verdict: PASS
+    depends:
+      unsatisfiable-dependencies:
+        armel:
+        - libgirara-gtk3-4 (>= 0.4.5)
+      verdict: REJECTED_PERMANENTLY
+    implicit-deps:
+      implicit-deps:
+        broken-binaries:
+        - zathura-cb/armel
+      verdict: REJECTED_PERMANENTLY
+    piuparts:
+      piuparts-test-url: https://piuparts.debian.org/sid/source/z/zathura.html
+      test-results: pass
+      verdict: PASS
+    rc-bugs:
+      shared-bugs: []
+      unique-source-bugs:
+      - '1100000'
+      unique-target-bugs: []
+      verdict: REJECTED_PERMANENTLY
+    some-future-policy:
+      verdict: PASS
+  source: zathura
+";
+
+    #[test]
+    fn policy_info() {
+        let excuses = from_str(TEST_DATA).unwrap();
+        let policy_info = excuses.sources[0].policy_info.as_ref().unwrap();
+
+        let autopkgtest = policy_info.autopkgtest.as_ref().unwrap();
+        let results = &autopkgtest.results["zathura-pdf-poppler/0.3.3-1"];
+        assert_eq!(
+            results[&Architecture::Amd64].status,
+            AutopkgtestStatus::Regression
+        );
+        assert!(results[&Architecture::Amd64].artifacts_url.is_none());
+        assert!(results[&Architecture::Amd64].retry_url.is_some());
+        assert_eq!(
+            results[&Architecture::Arm64].status,
+            AutopkgtestStatus::Pass
+        );
+        assert!(results[&Architecture::Arm64].history_url.is_none());
+
+        assert_eq!(
+            policy_info.rc_bugs.as_ref().unwrap().unique_source_bugs,
+            vec![1100000]
+        );
+        assert_eq!(
+            policy_info.piuparts.as_ref().unwrap().test_results,
+            Some(PiupartsResult::Pass)
+        );
+        assert_eq!(
+            policy_info
+                .depends
+                .as_ref()
+                .unwrap()
+                .unsatisfiable_dependencies[&Architecture::Armel],
+            vec!["libgirara-gtk3-4 (>= 0.4.5)"]
+        );
+        assert_eq!(
+            policy_info
+                .build_depends
+                .as_ref()
+                .unwrap()
+                .unsatisfiable_arch_build_depends[&Architecture::I386],
+            vec!["libgirara-dev (>= 0.4.5)"]
+        );
+        assert_eq!(
+            policy_info.block.as_ref().unwrap().hints["block"]
+                .by
+                .as_deref(),
+            Some("freeze")
+        );
+        assert_eq!(
+            policy_info
+                .implicit_deps
+                .as_ref()
+                .unwrap()
+                .implicit_deps
+                .as_ref()
+                .unwrap()
+                .broken_binaries,
+            vec!["zathura-cb/armel"]
+        );
+        assert_eq!(policy_info.extras.len(), 1);
+        assert_eq!(
+            policy_info.extras["some-future-policy"].verdict,
+            Verdict::Pass
+        );
+        assert_eq!(policy_info.verdicts().count(), 10);
+    }
+
+    const GRAPH_TEST_DATA: &str = r"---
+generated-date: 2026-10-01 10:42:13.123456+00:00
+sources:
+- item-name: aa
+  source: aa
+  is-candidate: false
+  new-version: 1-1
+  old-version: 1-0
+  invalidated-by-other-package: true
+  dependencies:
+    blocked-by:
+    - bb
+  excuses: []
+  migration-policy-verdict: REJECTED_BLOCKED_BY_ANOTHER_ITEM
+- item-name: bb
+  source: bb
+  is-candidate: false
+  new-version: 1-1
+  old-version: 1-0
+  invalidated-by-other-package: true
+  dependencies:
+    migrate-after:
+    - cc
+    blocked-by:
+    - not-in-excuses
+  excuses: []
+  migration-policy-verdict: REJECTED_WAITING_FOR_ANOTHER_ITEM
+- item-name: cc
+  source: cc
+  is-candidate: false
+  new-version: 1-1
+  old-version: 1-0
+  excuses: []
+  migration-policy-verdict: REJECTED_PERMANENTLY
+- item-name: dd
+  source: dd
+  is-candidate: true
+  new-version: 1-1
+  old-version: 1-0
+  excuses: []
+  migration-policy-verdict: PASS
+- item-name: ee
+  source: ee
+  is-candidate: false
+  new-version: 1-1
+  old-version: 1-0
+  dependencies:
+    blocked-by:
+    - ff
+  excuses: []
+  migration-policy-verdict: REJECTED_BLOCKED_BY_ANOTHER_ITEM
+- item-name: ff
+  source: ff
+  is-candidate: false
+  new-version: 1-1
+  old-version: 1-0
+  dependencies:
+    blocked-by:
+    - ee
+  excuses: []
+  migration-policy-verdict: REJECTED_BLOCKED_BY_ANOTHER_ITEM
+";
+
+    fn item_names(items: &[&ExcusesItem]) -> Vec<String> {
+        let mut names: Vec<_> = items
+            .iter()
+            .map(|item| item.item_name.to_string())
+            .collect();
+        names.sort_unstable();
+        names
+    }
+
+    #[test]
+    fn migration_graph() {
+        let excuses = from_str(GRAPH_TEST_DATA).unwrap();
+        let graph = excuses.migration_graph();
+
+        assert_eq!(
+            item_names(&graph.blockers(&"aa".parse().unwrap())),
+            vec!["bb", "cc"]
+        );
+        assert_eq!(
+            item_names(&graph.root_blockers(&"aa".parse().unwrap())),
+            vec!["cc"]
+        );
+        assert!(graph.blockers(&"dd".parse().unwrap()).is_empty());
+        assert!(graph.root_blockers(&"unknown".parse().unwrap()).is_empty());
+        assert_eq!(
+            item_names(&graph.root_blockers(&"ee".parse().unwrap())),
+            vec!["ff"]
+        );
+
+        let groups: Vec<_> = graph
+            .migration_groups()
+            .iter()
+            .map(|group| item_names(group))
+            .collect();
+        assert_eq!(groups, vec![vec!["aa", "bb", "cc"], vec!["ee", "ff"]]);
+    }
+
+    #[test]
+    fn migration_items() {
+        let item = MigrationItem::try_from("zathura").unwrap();
+        assert_eq!(item, MigrationItem::new("zathura".try_into().unwrap()));
+
+        let item = MigrationItem::try_from("-zathura").unwrap();
+        assert!(item.removal);
+        assert!(item.architecture.is_none());
+
+        let item = MigrationItem::try_from("zathura/amd64").unwrap();
+        assert_eq!(item.architecture, Some(Architecture::Amd64));
+        assert!(item.suite.is_none());
+
+        let item = MigrationItem::try_from("zathura/arm64_tpu").unwrap();
+        assert_eq!(item.architecture, Some(Architecture::Arm64));
+        assert_eq!(item.suite, Some(MigrationSuite::TestingProposedUpdates));
+
+        let item = MigrationItem::try_from("zathura_pu").unwrap();
+        assert_eq!(item.suite, Some(MigrationSuite::ProposedUpdates));
+
+        let item = MigrationItem::try_from("-zathura/1:0.5.11-1/i386").unwrap();
+        assert!(item.removal);
+        assert_eq!(
+            item.version,
+            Some(PackageVersion::try_from("1:0.5.11-1").unwrap())
+        );
+        assert_eq!(item.architecture, Some(Architecture::I386));
+
+        for name in [
+            "zathura",
+            "-zathura",
+            "zathura/amd64",
+            "zathura/arm64_tpu",
+            "zathura_pu",
+            "-zathura/1:0.5.11-1/i386",
+        ] {
+            assert_eq!(MigrationItem::try_from(name).unwrap().to_string(), name);
+        }
+    }
+
+    #[test]
+    fn invalid_migration_items() {
+        assert!(matches!(
+            MigrationItem::try_from("zathura/newport"),
+            Err(MigrationItemError::InvalidArchitecture(_))
+        ));
+        assert!(matches!(
+            MigrationItem::try_from("zathura_foo"),
+            Err(MigrationItemError::InvalidSuite(_))
+        ));
+        assert!(matches!(
+            MigrationItem::try_from("z"),
+            Err(MigrationItemError::InvalidPackage(_))
+        ));
+        assert!(matches!(
+            MigrationItem::try_from("zathura/amd64/i386"),
+            Err(MigrationItemError::TooManyComponents)
+        ));
+
+        // suite after the source package as in hints
+        assert_eq!(
+            MigrationItem::try_from("zathura_tpu/0.5.11-1").unwrap(),
+            MigrationItem::try_from("zathura/0.5.11-1_tpu").unwrap()
+        );
+        assert!(matches!(
+            MigrationItem::try_from("zathura_tpu/0.5.11-1_pu"),
+            Err(MigrationItemError::InvalidSuite(_))
+        ));
+
+        let item = MigrationItem::new("zathura".try_into().unwrap())
+            .with_version("0.5.11-1+b1".try_into().unwrap())
+            .with_architecture(Architecture::Amd64)
+            .with_suite(MigrationSuite::TestingProposedUpdates);
+        assert_eq!(item.to_string(), "zathura/0.5.11-1+b1/amd64_tpu");
+        assert_eq!(
+            item.hint_name().to_string(),
+            "zathura_tpu/0.5.11-1+b1/amd64"
+        );
+        assert_eq!(
+            MigrationItem::try_from(item.hint_name().to_string().as_str()).unwrap(),
+            item
+        );
+    }
+
+    #[test]
+    fn serialize_round_trip() {
+        for data in [TEST_DATA, GRAPH_TEST_DATA] {
+            let excuses = from_str(data).unwrap();
+            let serialized = serde_yaml::to_string(&excuses).unwrap();
+            assert_eq!(from_str(&serialized).unwrap(), excuses);
+        }
+    }
+
+    #[test]
+    fn retain_policies() {
+        let mut excuses = from_str(TEST_DATA).unwrap();
+        let policy_info = excuses.sources[0].policy_info.as_mut().unwrap();
+        policy_info.retain(|_, verdict| verdict != Verdict::Pass);
+
+        assert!(policy_info.age.is_none());
+        assert!(policy_info.piuparts.is_none());
+        assert!(policy_info.extras.is_empty());
+        assert!(policy_info.autopkgtest.is_some());
+        assert_eq!(policy_info.verdicts().count(), 6);
+    }
+
+    #[test]
+    fn streaming() {
+        for data in [TEST_DATA, GRAPH_TEST_DATA] {
+            let excuses = from_str(data).unwrap();
+            let reader = ExcusesReader::new(data.as_bytes()).unwrap();
+            assert_eq!(reader.generated_date(), excuses.generated_date);
+            let items: Vec<_> = reader.collect::<Result<_>>().unwrap();
+            assert_eq!(items, excuses.sources);
+        }
+    }
+
+    #[test]
+    fn streaming_fallback() {
+        let excuses = from_str(GRAPH_TEST_DATA).unwrap();
+        let indented = format!(
+            "generated-date: {}\nsources:\n{}",
+            excuses.generated_date.format("%Y-%m-%d %H:%M:%S%.6f%:z"),
+            serde_yaml::to_string(&excuses.sources)
+                .unwrap()
+                .lines()
+                .map(|line| format!("  {line}\n"))
+                .collect::<String>()
+        );
+        let items: Vec<_> = ExcusesReader::new(indented.as_bytes())
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(items, excuses.sources);
+
+        let json = r#"{"generated-date": "2026-10-01 10:42:13.123456+00:00", "sources": [{"item-name": "zathura", "source": "zathura", "is-candidate": true, "new-version": "1-1", "old-version": "1-0", "excuses": [], "migration-policy-verdict": "PASS"}]}"#;
+        let items: Vec<_> = ExcusesReader::new(json.as_bytes())
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(items, from_str(json).unwrap().sources);
+    }
+
+    #[test]
+    fn index() {
+        let index: ExcusesIndex = from_str(TEST_DATA).unwrap().into();
+        assert_eq!(index.len(), 1);
+        assert_eq!(index.by_source("zathura").count(), 1);
+        assert_eq!(index.by_maintainer("Sebastian Ramacher").count(), 1);
+        assert_eq!(index.by_maintainer("sebastian ramacher").count(), 1);
+        assert_eq!(index.by_source("girara").count(), 0);
+        assert_eq!(index.by_source_or_maintainer("zathura").count(), 1);
+        assert_eq!(index.by_source_or_maintainer("Zathura").count(), 1);
+        assert_eq!(
+            index.by_source_or_maintainer("SEBASTIAN RAMACHER").count(),
+            1
+        );
+    }
+}
@@ -0,0 +1,661 @@
+// Copyright 2026 Sebastian Ramacher
+// SPDX-License-Identifier: LGPL-3.0-or-later
+
+//! # Helpers to handle hints for britney
+//!
+//! This module provides builders to generate hints for [britney](https://release.debian.org/doc/britney/hints.html)
+//! as well as a parser and serializer for hint files.
+
+use std::{
+    fmt::{Display, Formatter},
+    io::BufRead,
+    str::FromStr,
+};
+
+use itertools::Itertools;
+use thiserror::Error;
+
+use crate::{
+    excuses::{MigrationItem, MigrationItemError},
+    package::PackageName,
+};
+
+/// Errors when working with hints
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("invalid item: {0}")]
+    /// An item of a hint is invalid
+    InvalidItem(#[from] MigrationItemError),
+    #[error("invalid package: {0}")]
+    /// A package of a hint is invalid
+    InvalidPackage(#[from] crate::package::PackageError),
+    #[error("item {} of hint '{}' requires a version", .0.hint_name(), .1)]
+    /// The hint requires versioned items
+    MissingVersion(HintItem, &'static str),
+    #[error("item {} of hint '{}' must not have a version", .0.hint_name(), .1)]
+    /// The hint requires unversioned items
+    UnexpectedVersion(HintItem, &'static str),
+    #[error("hint '{0}' requires at least one item")]
+    /// The hint has no items
+    MissingItems(&'static str),
+    #[error("empty hint")]
+    /// The line does not contain a hint
+    EmptyHint,
+    #[error("invalid number of days: {0}")]
+    /// The number of days of an `age-days` hint is invalid
+    InvalidDays(String),
+    #[error("unable to read hints: {0}")]
+    /// Reading the hints failed with IO error
+    IOError(#[from] std::io::Error),
+}
+
+/// An item of a hint
+///
+/// Hints refer to the same items as the excuses. In hint files, the suite is placed after the
+/// source package, e.g., `zathura_tpu/0.5.11-1`, see [MigrationItem::hint_name].
+pub type HintItem = MigrationItem;
+
+/// A hint for britney
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Hint {
+    /// Unblock items blocked by the freeze
+    Unblock(Vec<HintItem>),
+    /// Unblock udebs of the items
+    UnblockUdeb(Vec<HintItem>),
+    /// Override the age requirement of the items
+    AgeDays(u32, Vec<HintItem>),
+    /// Migrate the items without waiting for the age requirement
+    Urgent(Vec<HintItem>),
+    /// Block the migration of source packages
+    Block(Vec<HintItem>),
+    /// Block the migration of udebs of source packages
+    BlockUdeb(Vec<HintItem>),
+    /// Remove the items from testing
+    Remove(Vec<HintItem>),
+    /// Force the migration of the items ignoring all policies
+    Force(Vec<HintItem>),
+    /// Force the migration of the items even if it breaks installability
+    ForceHint(Vec<HintItem>),
+    /// Try to migrate the items together
+    Easy(Vec<HintItem>),
+    /// Try to migrate the items together including their reverse dependencies
+    Hint(Vec<HintItem>),
+    /// Allow the binary packages to become uninstallable
+    AllowUninst(Vec<PackageName>),
+    /// Ignore the results of the autopkgtests of the items
+    ForceSkiptest(Vec<HintItem>),
+    /// Allow smooth updates of the items' libraries in any section
+    AllowSmoothUpdate(Vec<HintItem>),
+    /// A hint that is not interpreted, e.g., `block-all` or `force-badtest`
+    ///
+    /// The arguments are kept as is.
+    Other {
+        /// Name of the hint
+        name: String,
+        /// Arguments of the hint
+        tokens: Vec<String>,
+    },
+}
+
+impl Hint {
+    /// Name of the hint as used in hint files
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Other { name, .. } => name,
+            _ => self.known_name().unwrap_or_default(),
+        }
+    }
+
+    /// Name of the hints that are interpreted
+    fn known_name(&self) -> Option<&'static str> {
+        Some(match self {
+            Self::Unblock(_) => "unblock",
+            Self::UnblockUdeb(_) => "unblock-udeb",
+            Self::AgeDays(_, _) => "age-days",
+            Self::Urgent(_) => "urgent",
+            Self::Block(_) => "block",
+            Self::BlockUdeb(_) => "block-udeb",
+            Self::Remove(_) => "remove",
+            Self::Force(_) => "force",
+            Self::ForceHint(_) => "force-hint",
+            Self::Easy(_) => "easy",
+            Self::Hint(_) => "hint",
+            Self::AllowUninst(_) => "allow-uninst",
+            Self::ForceSkiptest(_) => "force-skiptest",
+            Self::AllowSmoothUpdate(_) => "allow-smooth-update",
+            Self::Other { .. } => return None,
+        })
+    }
+
+    /// Items of the hint
+    ///
+    /// `allow-uninst` refers to binary packages and uninterpreted hints are not parsed, so both have
+    /// no items.
+    pub fn items(&self) -> &[HintItem] {
+        match self {
+            Self::Unblock(items)
+            | Self::UnblockUdeb(items)
+            | Self::AgeDays(_, items)
+            | Self::Urgent(items)
+            | Self::Block(items)
+            | Self::BlockUdeb(items)
+            | Self::Remove(items)
+            | Self::Force(items)
+            | Self::ForceHint(items)
+            | Self::Easy(items)
+            | Self::Hint(items)
+            | Self::ForceSkiptest(items)
+            | Self::AllowSmoothUpdate(items) => items,
+            Self::AllowUninst(_) | Self::Other { .. } => &[],
+        }
+    }
+
+    /// Check that the items are versioned as required by the hint
+    fn validate(self) -> Result<Self, Error> {
+        let Some(name) = self.known_name() else {
+            return Ok(self);
+        };
+        if let Self::AllowUninst(packages) = &self {
+            return if packages.is_empty() {
+                Err(Error::MissingItems(name))
+            } else {
+                Ok(self)
+            };
+        }
+        if self.items().is_empty() {
+            return Err(Error::MissingItems(name));
+        }
+        for item in self.items() {
+            match (&self, &item.version) {
+                // block hints refer to source packages
+                (Self::Block(_) | Self::BlockUdeb(_), Some(_)) => {
+                    return Err(Error::UnexpectedVersion(item.clone(), name));
+                }
+                (Self::Block(_) | Self::BlockUdeb(_), None) => {}
+                (_, None) => return Err(Error::MissingVersion(item.clone(), name)),
+                (_, Some(_)) => {}
+            }
+        }
+        Ok(self)
+    }
+}
+
+impl Display for Hint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())?;
+        match self {
+            Self::AgeDays(days, items) => write!(
+                f,
+                " {days} {}",
+                items.iter().map(HintItem::hint_name).join(" ")
+            ),
+            Self::AllowUninst(packages) => write!(f, " {}", packages.iter().join(" ")),
+            Self::Other { tokens, .. } if tokens.is_empty() => Ok(()),
+            Self::Other { tokens, .. } => write!(f, " {}", tokens.iter().join(" ")),
+            _ => write!(
+                f,
+                " {}",
+                self.items().iter().map(HintItem::hint_name).join(" ")
+            ),
+        }
+    }
+}
+
+impl TryFrom<&str> for Hint {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let mut tokens = value.split_whitespace();
+        let name = tokens.next().unwrap_or_default();
+        fn parse_items<'a>(tokens: impl Iterator<Item = &'a str>) -> Result<Vec<HintItem>, Error> {
+            tokens
+                .map(|token| HintItem::try_from(token).map_err(Error::from))
+                .collect()
+        }
+
+        let hint = match name {
+            "unblock" => Self::Unblock(parse_items(tokens)?),
+            "unblock-udeb" => Self::UnblockUdeb(parse_items(tokens)?),
+            "age-days" => {
+                let days = tokens.next().unwrap_or_default();
+                Self::AgeDays(
+                    days.parse()
+                        .map_err(|_| Error::InvalidDays(days.to_owned()))?,
+                    parse_items(tokens)?,
+                )
+            }
+            "urgent" => Self::Urgent(parse_items(tokens)?),
+            "block" => Self::Block(parse_items(tokens)?),
+            "block-udeb" => Self::BlockUdeb(parse_items(tokens)?),
+            "remove" => Self::Remove(parse_items(tokens)?),
+            "force" => Self::Force(parse_items(tokens)?),
+            "force-hint" => Self::ForceHint(parse_items(tokens)?),
+            "easy" => Self::Easy(parse_items(tokens)?),
+            "hint" => Self::Hint(parse_items(tokens)?),
+            "allow-uninst" => Self::AllowUninst(
+                tokens
+                    .map(PackageName::try_from)
+                    .collect::<Result<_, _>>()?,
+            ),
+            "force-skiptest" => Self::ForceSkiptest(parse_items(tokens)?),
+            "allow-smooth-update" => Self::AllowSmoothUpdate(parse_items(tokens)?),
+            "" => return Err(Error::EmptyHint),
+            // keep hints that are not interpreted
+            _ => Self::Other {
+                name: name.to_owned(),
+                tokens: tokens.map(ToOwned::to_owned).collect(),
+            },
+        };
+        hint.validate()
+    }
+}
+
+impl FromStr for Hint {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
+    }
+}
+
+/// A trait to build hints
+pub trait HintBuilder {
+    /// Build a hint
+    fn build(&self) -> Result<Hint, Error>;
+}
+
+macro_rules! items_hint_builder {
+    ($(#[$meta:meta])* $builder:ident, $variant:ident) => {
+        $(#[$meta])*
+        #[derive(Clone, Debug, Default, Eq, PartialEq)]
+        pub struct $builder {
+            items: Vec<HintItem>,
+        }
+
+        impl $builder {
+            /// Create a new hint for the given item.
+            pub fn new(item: HintItem) -> Self {
+                Self { items: vec![item] }
+            }
+
+            /// Add another item to the hint.
+            pub fn with_item(&mut self, item: HintItem) -> &mut Self {
+                self.items.push(item);
+                self
+            }
+        }
+
+        impl HintBuilder for $builder {
+            fn build(&self) -> Result<Hint, Error> {
+                Hint::$variant(self.items.clone()).validate()
+            }
+        }
+    };
+}
+
+items_hint_builder!(
+    /// Builder for the `unblock` hint
+    Unblock,
+    Unblock
+);
+items_hint_builder!(
+    /// Builder for the `unblock-udeb` hint
+    UnblockUdeb,
+    UnblockUdeb
+);
+items_hint_builder!(
+    /// Builder for the `urgent` hint
+    Urgent,
+    Urgent
+);
+items_hint_builder!(
+    /// Builder for the `block` hint
+    Block,
+    Block
+);
+items_hint_builder!(
+    /// Builder for the `block-udeb` hint
+    BlockUdeb,
+    BlockUdeb
+);
+items_hint_builder!(
+    /// Builder for the `remove` hint
+    Remove,
+    Remove
+);
+items_hint_builder!(
+    /// Builder for the `force` hint
+    Force,
+    Force
+);
+items_hint_builder!(
+    /// Builder for the `force-hint` hint
+    ForceHint,
+    ForceHint
+);
+items_hint_builder!(
+    /// Builder for the `easy` hint
+    Easy,
+    Easy
+);
+items_hint_builder!(
+    /// Builder for the `hint` hint
+    HintGroup,
+    Hint
+);
+items_hint_builder!(
+    /// Builder for the `force-skiptest` hint
+    ForceSkiptest,
+    ForceSkiptest
+);
+items_hint_builder!(
+    /// Builder for the `allow-smooth-update` hint
+    AllowSmoothUpdate,
+    AllowSmoothUpdate
+);
+
+/// Builder for the `age-days` hint
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AgeDays {
+    days: u32,
+    items: Vec<HintItem>,
+}
+
+impl AgeDays {
+    /// Create a new hint to set the age requirement of the given item.
+    pub fn new(days: u32, item: HintItem) -> Self {
+        Self {
+            days,
+            items: vec![item],
+        }
+    }
+
+    /// Add another item to the hint.
+    pub fn with_item(&mut self, item: HintItem) -> &mut Self {
+        self.items.push(item);
+        self
+    }
+}
+
+impl HintBuilder for AgeDays {
+    fn build(&self) -> Result<Hint, Error> {
+        Hint::AgeDays(self.days, self.items.clone()).validate()
+    }
+}
+
+/// Builder for the `allow-uninst` hint
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AllowUninst {
+    packages: Vec<PackageName>,
+}
+
+impl AllowUninst {
+    /// Create a new hint for the given binary package.
+    pub fn new(package: PackageName) -> Self {
+        Self {
+            packages: vec![package],
+        }
+    }
+
+    /// Add another binary package to the hint.
+    pub fn with_package(&mut self, package: PackageName) -> &mut Self {
+        self.packages.push(package);
+        self
+    }
+}
+
+impl HintBuilder for AllowUninst {
+    fn build(&self) -> Result<Hint, Error> {
+        Hint::AllowUninst(self.packages.clone()).validate()
+    }
+}
+
+/// An entry of a hint file
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HintEntry {
+    /// An empty line
+    Empty,
+    /// A comment including the leading `#`
+    Comment(String),
+    /// A hint
+    Hint(Hint),
+}
+
+impl Display for HintEntry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => Ok(()),
+            Self::Comment(comment) => write!(f, "{comment}"),
+            Self::Hint(hint) => write!(f, "{hint}"),
+        }
+    }
+}
+
+impl TryFrom<&str> for HintEntry {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let value = value.trim();
+        if value.is_empty() {
+            Ok(Self::Empty)
+        } else if value.starts_with('#') {
+            Ok(Self::Comment(value.to_owned()))
+        } else {
+            Hint::try_from(value).map(Self::Hint)
+        }
+    }
+}
+
+/// A hint file
+///
+/// britney ignores all hints after the `finished` line. They are kept in a separate section to
+/// preserve them when writing the hint file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Hints {
+    /// Active entries
+    pub entries: Vec<HintEntry>,
+    /// Entries after the `finished` line, if the file contains one
+    pub finished: Option<Vec<HintEntry>>,
+}
+
+impl Hints {
+    /// Iterate over the active hints
+    pub fn hints(&self) -> impl Iterator<Item = &Hint> {
+        self.entries.iter().filter_map(|entry| match entry {
+            HintEntry::Hint(hint) => Some(hint),
+            _ => None,
+        })
+    }
+}
+
+impl Display for Hints {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for entry in &self.entries {
+            writeln!(f, "{entry}")?;
+        }
+        if let Some(finished) = &self.finished {
+            writeln!(f, "finished")?;
+            for entry in finished {
+                writeln!(f, "{entry}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<&str> for Hints {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        from_reader(value.as_bytes())
+    }
+}
+
+impl FromStr for Hints {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
+    }
+}
+
+/// Read a hint file from a reader
+pub fn from_reader(reader: impl BufRead) -> Result<Hints, Error> {
+    let mut hints = Hints::default();
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim() == "finished" && hints.finished.is_none() {
+            hints.finished = Some(Vec::new());
+            continue;
+        }
+        let entry = HintEntry::try_from(line.as_str())?;
+        match &mut hints.finished {
+            Some(finished) => finished.push(entry),
+            None => hints.entries.push(entry),
+        }
+    }
+    Ok(hints)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{architectures::Architecture, excuses::MigrationSuite, version::PackageVersion};
+
+    #[test]
+    fn items() {
+        for item in [
+            "zathura/0.5.11-1",
+            "zathura_tpu/0.5.11-1",
+            "zathura/0.5.11-1+b1/amd64",
+            "-zathura/0.5.11-1",
+            "zathura",
+        ] {
+            assert_eq!(
+                HintItem::try_from(item).unwrap().hint_name().to_string(),
+                item
+            );
+        }
+
+        let item = HintItem::try_from("zathura_pu/0.5.11-1+b1/amd64").unwrap();
+        assert_eq!(item.suite, Some(MigrationSuite::ProposedUpdates));
+        assert_eq!(item.architecture, Some(Architecture::Amd64));
+        assert!(HintItem::try_from("zathura_foo/1.0").is_err());
+        assert!(HintItem::try_from("zathura/1.0/amd64/i386").is_err());
+    }
+
+    #[test]
+    fn builders() {
+        let source = PackageName::try_from("zathura").unwrap();
+        let version = PackageVersion::try_from("0.5.11-1").unwrap();
+        let item = HintItem::new(source.clone()).with_version(version.clone());
+
+        assert_eq!(
+            Unblock::new(item.clone()).build().unwrap().to_string(),
+            "unblock zathura/0.5.11-1"
+        );
+        assert_eq!(
+            Unblock::new(
+                item.clone()
+                    .with_suite(MigrationSuite::TestingProposedUpdates)
+            )
+            .build()
+            .unwrap()
+            .to_string(),
+            "unblock zathura_tpu/0.5.11-1"
+        );
+        assert_eq!(
+            AgeDays::new(2, item.clone())
+                .with_item(
+                    HintItem::new("girara".try_into().unwrap())
+                        .with_version("0.4.5-1".try_into().unwrap())
+                )
+                .build()
+                .unwrap()
+                .to_string(),
+            "age-days 2 zathura/0.5.11-1 girara/0.4.5-1"
+        );
+        assert_eq!(
+            Block::new(HintItem::new(source.clone()))
+                .build()
+                .unwrap()
+                .to_string(),
+            "block zathura"
+        );
+        assert_eq!(
+            AllowUninst::new("zathura-pdf-poppler".try_into().unwrap())
+                .build()
+                .unwrap()
+                .to_string(),
+            "allow-uninst zathura-pdf-poppler"
+        );
+        assert_eq!(
+            HintGroup::new(item.clone()).build().unwrap().to_string(),
+            "hint zathura/0.5.11-1"
+        );
+
+        assert!(matches!(
+            Unblock::new(HintItem::new(source.clone())).build(),
+            Err(Error::MissingVersion(_, "unblock"))
+        ));
+        assert!(matches!(
+            Block::new(item).build(),
+            Err(Error::UnexpectedVersion(_, "block"))
+        ));
+    }
+
+    #[test]
+    fn hint_file() {
+        const HINTS: &str = r"# 20261001
+# waiting for the transition
+unblock zathura/0.5.11-1 girara/0.4.5-1
+age-days 2 mpv/0.40.0-1
+
+block-udeb debian-installer
+force-skiptest zathura_tpu/0.5.11-1
+finished
+# 20260901
+urgent zathura/0.5.10-1
+";
+
+        let hints = Hints::try_from(HINTS).unwrap();
+        assert_eq!(hints.entries.len(), 7);
+        assert_eq!(hints.finished.as_ref().unwrap().len(), 2);
+        assert_eq!(hints.hints().count(), 4);
+        assert_eq!(hints.hints().next().unwrap().items().len(), 2);
+        assert!(matches!(hints.hints().nth(1).unwrap(), Hint::AgeDays(2, _)));
+        assert_eq!(hints.to_string(), HINTS);
+
+        assert!(matches!(
+            Hints::try_from("unblock zathura"),
+            Err(Error::MissingVersion(_, "unblock"))
+        ));
+        assert!(matches!(
+            Hints::try_from("age-days foo zathura/1.0"),
+            Err(Error::InvalidDays(_))
+        ));
+    }
+
+    #[test]
+    fn other_hints() {
+        const HINTS: &str = r"# 20261001
+block-all source
+force-badtest zathura/0.5.11-1/armel
+ignore-rc-bugs 1100001 zathura/0.5.11-1
+unblock zathura/0.5.11-1
+";
+
+        let hints = Hints::try_from(HINTS).unwrap();
+        assert_eq!(hints.hints().count(), 4);
+        assert_eq!(
+            hints.hints().next().unwrap(),
+            &Hint::Other {
+                name: "block-all".into(),
+                tokens: vec!["source".into()]
+            }
+        );
+        assert_eq!(hints.hints().nth(1).unwrap().name(), "force-badtest");
+        assert!(hints.hints().nth(1).unwrap().items().is_empty());
+        assert_eq!(hints.to_string(), HINTS);
+    }
+}
@@ -8,7 +8,10 @@
 //! * [archive]: Helpers for various features of the Debian archive
 //! * [autoremovals]: Helpers to handle autoremovals
 //! * [buildinfo]: Helpers to handle `.buildinfo` files
+//! * [distribution]: Helpers to handle distribution profiles
 //! * [excuses]: Helpers to handle `excuses.yaml` for testing migration
+//! * [hints]: Helpers to generate and parse hints for britney
+//! * [maintainer]: Helpers to handle maintainer fields
 //! * [package]: Helpers to handle package names
 //! * [release]: Helpers to handle `Release` files
 //! * [version]: Helpers to handle package versions
@@ -24,7 +27,10 @@ pub mod architectures;
 pub mod archive;
 pub mod autoremovals;
 pub mod buildinfo;
+pub mod distribution;
 pub mod excuses;
+pub mod hints;
+pub mod maintainer;
 pub mod package;
 pub mod release;
 mod utils;
@@ -0,0 +1,141 @@
+// Copyright 2026 Sebastian Ramacher
+// SPDX-License-Identifier: LGPL-3.0-or-later
+
+//! # Helpers to handle maintainer fields
+//!
+//! This module provides helpers to parse the `Maintainer` and `Uploaders` fields of Debian
+//! packages into names and email addresses.
+
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+use crate::utils::TryFromStrVisitor;
+
+/// A maintainer or uploader
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Maintainer {
+    /// The name
+    pub name: String,
+    /// The email address
+    pub email: Option<String>,
+}
+
+impl Maintainer {
+    /// Check if the name or the email address are equal to the given string ignoring case
+    pub fn matches(&self, name_or_email: &str) -> bool {
+        self.name.eq_ignore_ascii_case(name_or_email)
+            || self
+                .email
+                .as_ref()
+                .is_some_and(|email| email.eq_ignore_ascii_case(name_or_email))
+    }
+}
+
+impl From<&str> for Maintainer {
+    fn from(value: &str) -> Self {
+        let value = value.trim();
+        match value.rsplit_once('<') {
+            Some((name, email)) if email.ends_with('>') => Self {
+                name: name.trim().trim_matches('"').to_owned(),
+                email: Some(email[..email.len() - 1].trim().to_owned()),
+            },
+            _ => Self {
+                name: value.trim_matches('"').to_owned(),
+                email: None,
+            },
+        }
+    }
+}
+
+impl Display for Maintainer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.email {
+            Some(email) => write!(f, "{} <{}>", self.name, email),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+impl Serialize for Maintainer {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Maintainer {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_str(TryFromStrVisitor::new("a maintainer"))
+    }
+}
+
+/// Parse a comma-separated list of maintainers, e.g., the `Uploaders` field
+///
+/// Commas in quoted names or in email addresses do not separate maintainers.
+pub fn parse_list(value: &str) -> Vec<Maintainer> {
+    let mut maintainers = Vec::new();
+    let mut in_quotes = false;
+    let mut in_email = false;
+    let mut start = 0;
+    for (idx, c) in value.char_indices() {
+        match c {
+            '"' if !in_email => in_quotes = !in_quotes,
+            '<' if !in_quotes => in_email = true,
+            '>' if !in_quotes => in_email = false,
+            ',' if !in_quotes && !in_email => {
+                maintainers.push(&value[start..idx]);
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    maintainers.push(&value[start..]);
+
+    maintainers
+        .into_iter()
+        .filter(|maintainer| !maintainer.trim().is_empty())
+        .map(Maintainer::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn maintainer() {
+        let maintainer = Maintainer::from("Sebastian Ramacher <sramacher@debian.org>");
+        assert_eq!(maintainer.name, "Sebastian Ramacher");
+        assert_eq!(maintainer.email.as_deref(), Some("sramacher@debian.org"));
+        assert!(maintainer.matches("sebastian ramacher"));
+        assert!(maintainer.matches("SRAMACHER@debian.org"));
+        assert!(!maintainer.matches("sramacher"));
+        assert_eq!(
+            maintainer.to_string(),
+            "Sebastian Ramacher <sramacher@debian.org>"
+        );
+
+        let maintainer = Maintainer::from("Debian Multimedia Maintainers");
+        assert!(maintainer.email.is_none());
+    }
+
+    #[test]
+    fn list() {
+        let maintainers = parse_list(
+            r#"Sebastian Ramacher <sramacher@debian.org>, "Doe, Jane" <jane@example.org>,
+ Debian Go Packaging Team <team+pkg-go@tracker.debian.org>,"#,
+        );
+        assert_eq!(maintainers.len(), 3);
+        assert_eq!(maintainers[1].name, "Doe, Jane");
+        assert_eq!(
+            maintainers[2].email.as_deref(),
+            Some("team+pkg-go@tracker.debian.org")
+        );
+    }
+}
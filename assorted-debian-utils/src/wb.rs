@@ -35,6 +35,9 @@ pub enum Error {
     #[error("unable to exectue 'wb': {0}")]
     /// Execution of `wb` failed with IO error
     IOError(#[from] std::io::Error),
+    #[error("invalid build state: {0}")]
+    /// An unknown build state was encountered
+    InvalidBuildState(String),
 }
 
 /// A command to be executed by `wb`
@@ -348,6 +351,42 @@ impl WBCommandBuilder for DepWait<'_> {
     }
 }
 
+/// Builder for the `gb` command
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GiveBack<'a> {
+    source: &'a SourceSpecifier<'a>,
+}
+
+impl<'a> GiveBack<'a> {
+    /// Create a new `gb` command for the given `source`.
+    pub fn new(source: &'a SourceSpecifier<'a>) -> Result<Self, Error> {
+        for arch in &source.architectures {
+            match arch {
+                // unable to gb with source, -source
+                WBArchitecture::Architecture(Architecture::Source)
+                | WBArchitecture::ExcludeArchitecture(Architecture::Source) => {
+                    return Err(Error::InvalidArchitecture(*arch, "gb"));
+                }
+                _ => {}
+            }
+        }
+
+        Ok(Self { source })
+    }
+}
+
+impl Display for GiveBack<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "gb {}", self.source)
+    }
+}
+
+impl WBCommandBuilder for GiveBack<'_> {
+    fn build(&self) -> WBCommand {
+        WBCommand(self.to_string())
+    }
+}
+
 /// Builder for the `bp` command
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct BuildPriority<'a> {
@@ -458,10 +497,115 @@ impl WBCommandBuilder for Info<'_> {
     }
 }
 
+/// States of builds in wanna-build
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BuildState {
+    /// `Needs-Build`
+    NeedsBuild,
+    /// `Building`
+    Building,
+    /// `Built`
+    Built,
+    /// `Build-Attempted`
+    BuildAttempted,
+    /// `Maybe-Failed`
+    MaybeFailed,
+    /// `Maybe-Successful`
+    MaybeSuccessful,
+    /// `Uploaded`
+    Uploaded,
+    /// `Installed`
+    Installed,
+    /// `Install-Wait`
+    InstallWait,
+    /// `Reupload-Wait`
+    ReuploadWait,
+    /// `Failed`
+    Failed,
+    /// `Failed-Removed`
+    FailedRemoved,
+    /// `Dep-Wait`
+    DepWait,
+    /// `Dep-Wait-Removed`
+    DepWaitRemoved,
+    /// `BD-Uninstallable`
+    BDUninstallable,
+    /// `Not-For-Us`
+    NotForUs,
+    /// `Auto-Not-For-Us`
+    AutoNotForUs,
+}
+
+impl AsRef<str> for BuildState {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::NeedsBuild => "Needs-Build",
+            Self::Building => "Building",
+            Self::Built => "Built",
+            Self::BuildAttempted => "Build-Attempted",
+            Self::MaybeFailed => "Maybe-Failed",
+            Self::MaybeSuccessful => "Maybe-Successful",
+            Self::Uploaded => "Uploaded",
+            Self::Installed => "Installed",
+            Self::InstallWait => "Install-Wait",
+            Self::ReuploadWait => "Reupload-Wait",
+            Self::Failed => "Failed",
+            Self::FailedRemoved => "Failed-Removed",
+            Self::DepWait => "Dep-Wait",
+            Self::DepWaitRemoved => "Dep-Wait-Removed",
+            Self::BDUninstallable => "BD-Uninstallable",
+            Self::NotForUs => "Not-For-Us",
+            Self::AutoNotForUs => "Auto-Not-For-Us",
+        }
+    }
+}
+
+impl Display for BuildState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_ref())
+    }
+}
+
+impl TryFrom<&str> for BuildState {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "Needs-Build" => Ok(Self::NeedsBuild),
+            "Building" => Ok(Self::Building),
+            "Built" => Ok(Self::Built),
+            "Build-Attempted" => Ok(Self::BuildAttempted),
+            "Maybe-Failed" => Ok(Self::MaybeFailed),
+            "Maybe-Successful" => Ok(Self::MaybeSuccessful),
+            "Uploaded" => Ok(Self::Uploaded),
+            "Installed" => Ok(Self::Installed),
+            "Install-Wait" => Ok(Self::InstallWait),
+            "Reupload-Wait" => Ok(Self::ReuploadWait),
+            "Failed" => Ok(Self::Failed),
+            "Failed-Removed" => Ok(Self::FailedRemoved),
+            "Dep-Wait" => Ok(Self::DepWait),
+            "Dep-Wait-Removed" => Ok(Self::DepWaitRemoved),
+            "BD-Uninstallable" => Ok(Self::BDUninstallable),
+            "Not-For-Us" => Ok(Self::NotForUs),
+            "Auto-Not-For-Us" => Ok(Self::AutoNotForUs),
+            _ => Err(Error::InvalidBuildState(value.to_owned())),
+        }
+    }
+}
+
+impl FromStr for BuildState {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::{
-        BinNMU, BuildPriority, DepWait, Fail, SourceSpecifier, WBArchitecture, WBCommandBuilder,
+        BinNMU, BuildPriority, BuildState, DepWait, Fail, GiveBack, SourceSpecifier,
+        WBArchitecture, WBCommandBuilder,
     };
     use crate::{
         architectures::Architecture,
@@ -691,4 +835,48 @@ mod test {
             "fail zathura . ANY . unstable . -m \"#1234\""
         );
     }
+
+    #[test]
+    fn gb() {
+        let source = PackageName::try_from("zathura").unwrap();
+        let version = "0.5.11-1".try_into().unwrap();
+
+        assert_eq!(
+            GiveBack::new(
+                SourceSpecifier::new(&source)
+                    .with_version(&version)
+                    .with_archive_architectures(&[Architecture::Amd64])
+            )
+            .unwrap()
+            .build()
+            .to_string(),
+            "gb zathura_0.5.11-1 . amd64 . unstable"
+        );
+        assert!(
+            GiveBack::new(
+                SourceSpecifier::new(&source).with_archive_architectures(&[Architecture::Source])
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn build_state() {
+        for state in [
+            "Build-Attempted",
+            "Maybe-Failed",
+            "BD-Uninstallable",
+            "Install-Wait",
+            "Reupload-Wait",
+            "Failed-Removed",
+            "Dep-Wait-Removed",
+        ] {
+            assert_eq!(BuildState::try_from(state).unwrap().to_string(), state);
+        }
+        assert_eq!(
+            "Dep-Wait".parse::<BuildState>().unwrap(),
+            BuildState::DepWait
+        );
+        assert!(BuildState::try_from("Maybe-Broken").is_err());
+    }
 }
@@ -7,16 +7,30 @@ fn parse_excuses_2025_05_01() {
     parse_excuses("excuses-2025-05-01.yaml");
 }
 
+#[test]
+fn parse_excuses_sample() {
+    parse_excuses("excuses-sample.yaml");
+}
+
 fn parse_excuses(data_file: &str) {
     let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     let excuses_path = manifest_dir.join("tests").join("data").join(data_file);
-    let excuses_file = File::open(excuses_path).expect("Excuses file exists.");
+    let excuses_file = File::open(&excuses_path).expect("Excuses file exists.");
     let excuses = excuses::from_reader(BufReader::new(excuses_file)).expect("Excuses file parsed.");
 
+    let excuses_file = File::open(&excuses_path).expect("Excuses file exists.");
+    let reader =
+        excuses::ExcusesReader::new(BufReader::new(excuses_file)).expect("Excuses header parsed.");
+    assert_eq!(reader.generated_date(), excuses.generated_date);
+    let items: Vec<_> = reader
+        .collect::<excuses::Result<_>>()
+        .expect("Excuses items parsed.");
+    assert_eq!(items, excuses.sources);
+
     assert!(!excuses.sources.is_empty());
 
     for source in excuses.sources {
-        assert!(source.item_name.contains(source.source.as_ref()));
+        assert_eq!(source.item_name.source, source.source);
 
         if source.is_binnmu() {
             assert!(source.binnmu_arch().is_some());
@@ -3,7 +3,7 @@
 
 use std::{fs::File, io::BufReader, path::PathBuf};
 
-use assorted_debian_utils::release;
+use assorted_debian_utils::{distribution::DistributionProfile, release};
 
 #[test]
 fn parse_release_ramacher_unstable() {
@@ -44,3 +44,33 @@ fn parse_release(data_file: &str) {
     assert!(!archive.label.is_empty());
     assert!(!archive.files.is_empty());
 }
+
+#[test]
+fn distribution_profile_from_releases() {
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let data_dir = manifest_dir.join("tests").join("data");
+
+    let profile = DistributionProfile::from_releases(
+        "ramacher.at",
+        [BufReader::new(
+            File::open(data_dir.join("Release-ramacher.at-unstable"))
+                .expect("Release file opened."),
+        )],
+    )
+    .expect("Release file parse correctly.");
+    assert_eq!(profile.codename_for_suite("unstable"), Some("sid".into()));
+    assert!(profile.codename_for_suite("testing").is_none());
+
+    let profile = DistributionProfile::from_releases(
+        "Debian",
+        ["Release-debian-unstable", "Release-debian-forky"].map(|data_file| {
+            BufReader::new(File::open(data_dir.join(data_file)).expect("Release file opened."))
+        }),
+    )
+    .expect("Release files parse correctly.");
+    assert_eq!(profile.suite_for_codename("forky"), Some("testing".into()));
+    assert_eq!(
+        profile.suite_for_codename("forky-backports"),
+        Some("testing-backports".into())
+    );
+}
@@ -0,0 +1,249 @@
+// Copyright 2026 Sebastian Ramacher
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Report pending autoremovals grouped by removal date and sorted by impact
+
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, HashSet},
+    fmt::{self, Display},
+};
+
+use anyhow::Result;
+use assorted_debian_utils::{
+    autoremovals::{self, AutoRemoval, AutoRemovals},
+    package::PackageName,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::{
+    Command, Downloads,
+    cli::AutoRemovalsOptions,
+    config::{self, CacheEntries, FreezeSettings},
+};
+
+/// Impact of an autoremoval
+#[derive(Debug)]
+struct Impact<'a> {
+    autoremoval: &'a AutoRemoval,
+    key_package: bool,
+}
+
+impl Impact<'_> {
+    /// Number of reverse dependencies that would be removed together with the package
+    fn rdeps(&self) -> usize {
+        self.autoremoval.rdeps.as_ref().map_or(0, Vec::len)
+    }
+
+    /// Key for sorting by impact: key packages first, then by number of reverse dependencies
+    fn sort_key(&self) -> impl Ord + '_ {
+        (
+            Reverse(self.key_package),
+            Reverse(self.rdeps()),
+            &self.autoremoval.source,
+        )
+    }
+}
+
+impl Display for Impact<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {}: {} rdeps",
+            self.autoremoval.source,
+            self.autoremoval.version,
+            self.rdeps()
+        )?;
+        if self.key_package {
+            write!(f, " [key package]")?;
+        }
+        if self.autoremoval.dependencies_only {
+            write!(f, " [buggy dependencies only")?;
+            if let Some(buggy_dependencies) = &self.autoremoval.buggy_dependencies {
+                let buggy_dependencies: Vec<_> =
+                    buggy_dependencies.iter().map(AsRef::as_ref).collect();
+                write!(f, ": {}", buggy_dependencies.join(", "))?;
+            }
+            write!(f, "]")?;
+        } else if !self.autoremoval.bugs.is_empty() {
+            let bugs: Vec<_> = self
+                .autoremoval
+                .bugs
+                .iter()
+                .map(|bug| format!("#{bug}"))
+                .collect();
+            write!(f, " (bugs: {})", bugs.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Group autoremovals by removal date and sort each group by impact
+fn group_by_date<'a>(
+    autoremovals: &'a AutoRemovals,
+    key_packages: &HashSet<PackageName>,
+) -> BTreeMap<NaiveDate, Vec<Impact<'a>>> {
+    let mut groups: BTreeMap<_, Vec<_>> = BTreeMap::new();
+    for (package, autoremoval) in autoremovals {
+        groups
+            .entry(autoremoval.removal_date.date_naive())
+            .or_default()
+            .push(Impact {
+                autoremoval,
+                key_package: key_packages.contains(package),
+            });
+    }
+    for impacts in groups.values_mut() {
+        impacts.sort_by(|lhs, rhs| lhs.sort_key().cmp(&rhs.sort_key()));
+    }
+    groups
+}
+
+/// Describe when the removal is due relative to today
+fn due(date: NaiveDate, today: NaiveDate) -> String {
+    let days = (date - today).num_days();
+    if days < 0 {
+        format!("overdue by {} days", -days)
+    } else {
+        format!("in {days} days")
+    }
+}
+
+/// Print the report relative to the given time
+fn print_report(groups: &BTreeMap<NaiveDate, Vec<Impact<'_>>>, now: DateTime<Utc>) {
+    let today = now.date_naive();
+    for (date, impacts) in groups {
+        println!(
+            "# {date} ({}, {} packages)",
+            due(*date, today),
+            impacts.len()
+        );
+        for impact in impacts {
+            println!("{impact}");
+        }
+    }
+}
+
+pub(crate) struct AutoRemovalsReport<'a> {
+    cache: &'a config::Cache,
+    freeze_settings: FreezeSettings,
+}
+
+impl<'a> AutoRemovalsReport<'a> {
+    pub(crate) fn new(
+        cache: &'a config::Cache,
+        options: AutoRemovalsOptions,
+        mut freeze_settings: FreezeSettings,
+    ) -> Self {
+        if let Some(key_packages) = options.key_packages {
+            freeze_settings.key_packages = Some(key_packages);
+        }
+        Self {
+            cache,
+            freeze_settings,
+        }
+    }
+}
+
+impl Command for AutoRemovalsReport<'_> {
+    fn run(&self) -> Result<()> {
+        let autoremovals =
+            autoremovals::from_reader(self.cache.get_cache_bufreader("autoremovals.yaml")?)?;
+        let key_packages = self.freeze_settings.load_key_packages(self.cache)?;
+
+        print_report(&group_by_date(&autoremovals, &key_packages), Utc::now());
+        Ok(())
+    }
+}
+
+impl Downloads for AutoRemovalsReport<'_> {
+    fn downloads(&self) -> Vec<CacheEntries> {
+        let mut downloads = vec![CacheEntries::AutoRemovals];
+        downloads.extend(self.freeze_settings.key_packages_download());
+        downloads
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TEST_DATA: &str = r"---
+mplayer:
+  bugs:
+  - '1005899'
+  dependencies_only: false
+  last_checked: 2022-04-10 17:55:40+00:00
+  rdeps:
+  - devede
+  - mplayer-blue
+  - ogmrip
+  removal_date: 2022-05-01 19:42:01+00:00
+  source: mplayer
+  version: 2:1.4+ds1-3
+mplayer-blue:
+  buggy_dependencies:
+  - mplayer
+  bugs: []
+  bugs_dependencies:
+  - '1005899'
+  dependencies_only: true
+  last_checked: 2022-04-10 17:55:40+00:00
+  removal_date: 2022-05-01 19:42:01+00:00
+  source: mplayer-blue
+  version: 1.13-2
+zathura:
+  bugs:
+  - '1100001'
+  dependencies_only: false
+  last_checked: 2022-04-10 17:55:40+00:00
+  removal_date: 2022-05-01 08:00:00+00:00
+  source: zathura
+  version: 0.5.11-1
+girara:
+  bugs:
+  - '1100002'
+  dependencies_only: false
+  last_checked: 2022-04-10 17:55:40+00:00
+  rdeps:
+  - zathura
+  removal_date: 2022-05-08 19:42:01+00:00
+  source: girara
+  version: 0.4.5-1
+";
+
+    #[test]
+    fn impact() {
+        let autoremovals = autoremovals::from_str(TEST_DATA).unwrap();
+        let key_packages = HashSet::from([PackageName::try_from("zathura").unwrap()]);
+        let groups = group_by_date(&autoremovals, &key_packages);
+
+        assert_eq!(
+            groups.keys().map(ToString::to_string).collect::<Vec<_>>(),
+            vec!["2022-05-01", "2022-05-08"]
+        );
+        assert_eq!(
+            groups
+                .values()
+                .next()
+                .unwrap()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec![
+                "zathura 0.5.11-1: 0 rdeps [key package] (bugs: #1100001)",
+                "mplayer 2:1.4+ds1-3: 3 rdeps (bugs: #1005899)",
+                "mplayer-blue 1.13-2: 0 rdeps [buggy dependencies only: mplayer]",
+            ]
+        );
+
+        let today = NaiveDate::from_ymd_opt(2022, 5, 3).unwrap();
+        assert_eq!(
+            groups
+                .keys()
+                .map(|date| due(*date, today))
+                .collect::<Vec<_>>(),
+            vec!["overdue by 2 days", "in 5 days"]
+        );
+    }
+}
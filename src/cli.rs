@@ -4,9 +4,13 @@
 use std::{fmt, path::PathBuf, str::FromStr};
 
 use assorted_debian_utils::{
-    archive::SuiteOrCodename, package::PackageRelationship, wb::WBArchitecture,
+    archive::{Extension, SuiteOrCodename},
+    package::PackageRelationship,
+    wb::WBArchitecture,
 };
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
 use clap::{Parser, Subcommand};
+use serde::Deserialize;
 
 #[derive(Debug, Parser)]
 pub struct BaseOptions {
@@ -32,11 +36,142 @@ pub struct BaseOptions {
     /// Information on packages, sources and releases is downloaded from this mirror.
     #[clap(long, default_value = "https://deb.debian.org/debian")]
     pub mirror: String,
+    /// Archive mirror for an extension formatted as `$extension=$mirror`
+    ///
+    /// Suites with this extension (e.g., `stable-security`) are downloaded from
+    /// the given mirror instead. By default, the `security` extension is
+    /// downloaded from <https://security.debian.org/debian-security>.
+    #[clap(long = "extension-mirror")]
+    pub extension_mirrors: Vec<ExtensionMirror>,
+    /// Also consider architectures from debian-ports
+    ///
+    /// If this option is set, the architectures of unstable and experimental
+    /// also include those available from the debian-ports mirror.
+    #[clap(long)]
+    pub ports: bool,
+    /// debian-ports mirror
+    #[clap(long, default_value = "https://deb.debian.org/debian-ports")]
+    pub ports_mirror: String,
+    /// Run against the archive as it was at the given time
+    ///
+    /// Information on packages, sources and releases is downloaded from
+    /// snapshot.debian.org instead of the mirrors. The timestamp is either
+    /// given in the format used by snapshot.debian.org (e.g.,
+    /// `20260101T000000Z`), as RFC 3339 date and time, or as date. Files
+    /// downloaded from snapshots are cached separately.
+    ///
+    /// Only the archive, the security archive and debian-ports are historical.
+    /// Mirrors for other extensions are ignored. The excuses, autoremovals
+    /// and bug lists from UDD are not archived and reflect the current state.
+    #[clap(long)]
+    pub snapshot: Option<SnapshotTimestamp>,
+    /// snapshot.debian.org mirror
+    #[clap(long, default_value = "https://snapshot.debian.org")]
+    pub snapshot_mirror: String,
     /// `buildd` server
     ///
     /// To schedule `wanna-build` actions, a SSH connection is established to this server.
     #[clap(long, default_value = "wuiet.debian.org")]
     pub buildd: String,
+    /// Configuration file
+    ///
+    /// If not specified, `config.yaml` is read from the `Debian-RT-tools`
+    /// directory in `$XDG_CONFIG_HOME` if it exists.
+    #[clap(long)]
+    pub config: Option<PathBuf>,
+    #[clap(flatten)]
+    pub http_options: HttpOptions,
+}
+
+/// Options for HTTP downloads
+///
+/// These options override the settings from the `http` section of the
+/// configuration file.
+#[derive(Debug, Parser)]
+pub struct HttpOptions {
+    /// Proxy for all HTTP and HTTPS requests
+    #[clap(long)]
+    pub proxy: Option<String>,
+    /// Additional CA certificates
+    ///
+    /// The file is expected to contain one or more PEM encoded certificates.
+    /// This option can be specified multiple times.
+    #[clap(long = "ca-certificate")]
+    pub ca_certificates: Vec<PathBuf>,
+    /// Client certificate and private key
+    ///
+    /// The file is expected to contain a PEM encoded certificate and its
+    /// private key.
+    #[clap(long)]
+    pub client_identity: Option<PathBuf>,
+    /// Timeout in seconds to establish a connection
+    #[clap(long)]
+    pub connect_timeout: Option<u64>,
+    /// Timeout in seconds for stalled downloads
+    #[clap(long)]
+    pub read_timeout: Option<u64>,
+    /// User-Agent for HTTP requests
+    #[clap(long)]
+    pub user_agent: Option<String>,
+}
+
+/// Mirror for an archive extension
+#[derive(Clone, Debug)]
+pub struct ExtensionMirror {
+    pub extension: Extension,
+    pub mirror: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ExtensionMirrorParseError {
+    #[error("expected $extension=$mirror")]
+    MissingSeparator,
+    #[error("invalid extension: {0}")]
+    InvalidExtension(#[from] assorted_debian_utils::ParseError),
+}
+
+impl FromStr for ExtensionMirror {
+    type Err = ExtensionMirrorParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (extension, mirror) = s
+            .split_once('=')
+            .ok_or(ExtensionMirrorParseError::MissingSeparator)?;
+        Ok(Self {
+            extension: extension.parse()?,
+            mirror: mirror.to_string(),
+        })
+    }
+}
+
+/// Timestamp of an archive snapshot
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SnapshotTimestamp(pub DateTime<Utc>);
+
+impl fmt::Display for SnapshotTimestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.format("%Y%m%dT%H%M%SZ"))
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("invalid snapshot timestamp")]
+pub struct SnapshotTimestampParseError;
+
+impl FromStr for SnapshotTimestamp {
+    type Err = SnapshotTimestampParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(date_time) = NaiveDateTime::parse_from_str(s, "%Y%m%dT%H%M%SZ") {
+            Ok(Self(date_time.and_utc()))
+        } else if let Ok(date_time) = DateTime::parse_from_rfc3339(s) {
+            Ok(Self(date_time.to_utc()))
+        } else if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+            Ok(Self(date.and_time(Default::default()).and_utc()))
+        } else {
+            Err(SnapshotTimestampParseError)
+        }
+    }
 }
 
 #[derive(Debug, Parser)]
@@ -73,18 +208,157 @@ pub struct BinNMUsOptions {
     pub architecture: Option<Vec<WBArchitecture>>,
 }
 
+/// Format for machine-readable output
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum OutputFormat {
+    #[default]
+    Yaml,
+    Json,
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Yaml => write!(f, "yaml"),
+            Self::Json => write!(f, "json"),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("invalid output format, expected yaml or json")]
+pub struct OutputFormatParseError;
+
+impl FromStr for OutputFormat {
+    type Err = OutputFormatParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "yaml" => Ok(Self::Yaml),
+            "json" => Ok(Self::Json),
+            _ => Err(OutputFormatParseError),
+        }
+    }
+}
+
+/// Format of reports
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ReportFormat {
+    #[default]
+    Text,
+    Markdown,
+    Html,
+}
+
+impl fmt::Display for ReportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Text => write!(f, "text"),
+            Self::Markdown => write!(f, "markdown"),
+            Self::Html => write!(f, "html"),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("invalid report format, expected text, markdown or html")]
+pub struct ReportFormatParseError;
+
+impl FromStr for ReportFormat {
+    type Err = ReportFormatParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Self::Text),
+            "markdown" => Ok(Self::Markdown),
+            "html" => Ok(Self::Html),
+            _ => Err(ReportFormatParseError),
+        }
+    }
+}
+
 #[derive(Debug, Parser)]
 pub struct GrepExcusesOptions {
-    /// Currently not implemented
+    /// Show autopkgtest results
     ///
-    /// This is currently only provided as option for compatibility with `grep-excuses` from `devscripts`.
-    #[clap(long)]
+    /// Prints the per-architecture results of the autopkgtests for the matching items and lists
+    /// items whose migration is held back by regressions in the tests of the given packages. Only
+    /// available for text output.
+    #[clap(long, conflicts_with = "format")]
     pub autopkgtests: bool,
+    /// Write the matching excuses to the given file
+    ///
+    /// The file uses the same layout as `excuses.yaml` and can be processed by the same tools.
+    #[clap(long)]
+    pub export: Option<PathBuf>,
+    /// Format of the exported excuses (yaml or json)
+    #[clap(long, default_value_t = OutputFormat::Yaml)]
+    pub export_format: OutputFormat,
+    /// Format of the report (text, markdown or html)
+    ///
+    /// The Markdown and HTML reports group the items by maintainer and keep the links from the
+    /// excuses. They include the estimate of the earliest migration. The HTML report is a
+    /// standalone document.
+    #[clap(long, default_value_t = ReportFormat::Text)]
+    pub format: ReportFormat,
+    /// Only consider excuses that are not passing and only keep failing policies
+    #[clap(long)]
+    pub only_failing: bool,
+    /// Interpret the arguments as regular expressions
+    ///
+    /// Otherwise, arguments containing `*`, `?` or `[` are interpreted as glob patterns.
+    #[clap(long)]
+    pub regex: bool,
+    /// Also match packages by their uploaders
+    ///
+    /// The uploaders are read from the Sources file of unstable.
+    #[clap(long)]
+    pub uploaders: bool,
     /// The maintainer or package to grep for
+    ///
+    /// Maintainers are matched by name, email address, or both, ignoring case.
     #[clap(num_args = 1, required = true)]
     pub maintainer_package: Vec<String>,
 }
 
+#[derive(Debug, Parser)]
+pub struct ExcusesDiffOptions {
+    /// Compare against the given excuses instead of the previously downloaded ones
+    #[clap(long)]
+    pub previous: Option<PathBuf>,
+    /// Interpret the arguments as regular expressions
+    ///
+    /// Otherwise, arguments containing `*`, `?` or `[` are interpreted as glob patterns.
+    #[clap(long)]
+    pub regex: bool,
+    /// The maintainers or packages to report changes for
+    ///
+    /// If none are given, changes of all items are reported.
+    pub maintainer_package: Vec<String>,
+}
+
+#[derive(Debug, Parser)]
+pub struct AutoRemovalsOptions {
+    /// File with the list of key packages, one source package per line
+    ///
+    /// Overrides the file set in the configuration file. If neither is set, the list of key
+    /// packages is downloaded from UDD.
+    #[clap(long)]
+    pub key_packages: Option<PathBuf>,
+}
+
+#[derive(Debug, Parser)]
+pub struct HintsCheckOptions {
+    /// Write the hints without the obsolete items to the given file
+    ///
+    /// Hints without any remaining items are dropped. Comments and the hints after `finished`
+    /// are kept as is.
+    #[clap(long)]
+    pub output: Option<PathBuf>,
+    /// The hint file to check
+    pub hints: PathBuf,
+}
+
 #[derive(Debug, Parser)]
 pub struct BinNMUBuildinfoOptions {
     #[clap(flatten)]
@@ -93,6 +367,49 @@ pub struct BinNMUBuildinfoOptions {
     pub inputs: Vec<PathBuf>,
 }
 
+/// Phase of the freeze
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, PartialOrd, Ord, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FreezePhase {
+    #[default]
+    None,
+    Transition,
+    Soft,
+    Hard,
+    Full,
+}
+
+impl fmt::Display for FreezePhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::None => write!(f, "none"),
+            Self::Transition => write!(f, "transition"),
+            Self::Soft => write!(f, "soft"),
+            Self::Hard => write!(f, "hard"),
+            Self::Full => write!(f, "full"),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("invalid freeze phase, expected none, transition, soft, hard or full")]
+pub struct FreezePhaseParseError;
+
+impl FromStr for FreezePhase {
+    type Err = FreezePhaseParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Self::None),
+            "transition" => Ok(Self::Transition),
+            "soft" => Ok(Self::Soft),
+            "hard" => Ok(Self::Hard),
+            "full" => Ok(Self::Full),
+            _ => Err(FreezePhaseParseError),
+        }
+    }
+}
+
 #[derive(Debug, Parser)]
 pub struct ProcessExcusesOptions {
     /// Ignore age of packages
@@ -101,6 +418,42 @@ pub struct ProcessExcusesOptions {
     /// Ignore results from autopkgtests
     #[clap(long)]
     pub ignore_autopkgtests: bool,
+    /// Hint file to accept autopkgtest regressions covered by `force-skiptest` hints
+    #[clap(long)]
+    pub hints: Option<PathBuf>,
+    /// Phase of the freeze (none, transition, soft, hard or full)
+    ///
+    /// If not set, the phase is determined from the freeze dates in the configuration file.
+    #[clap(long)]
+    pub freeze_phase: Option<FreezePhase>,
+    /// File with the list of key packages, one source package per line
+    ///
+    /// Overrides the file set in the configuration file. If neither is set, the list of key
+    /// packages is downloaded from UDD.
+    #[clap(long)]
+    pub key_packages: Option<PathBuf>,
+    /// Print the decision for each item in a machine-readable format (yaml or json)
+    ///
+    /// The generated commands are included in the output instead of being executed.
+    #[clap(long)]
+    pub format: Option<OutputFormat>,
+    /// Plan give-backs for missing builds
+    ///
+    /// Builds in Build-Attempted or Maybe-Failed state are given back. For BD-Uninstallable
+    /// builds, a dep-wait is suggested.
+    #[clap(long)]
+    pub give_back: bool,
+    /// File with the wanna-build states of the builds instead of querying wanna-build
+    ///
+    /// Each line consists of `$source_$version $architecture $state`.
+    #[clap(long, requires = "give_back")]
+    pub wb_states: Option<PathBuf>,
+    /// Write email drafts asking for source-only uploads to this mbox
+    ///
+    /// One draft per maintainer is written for items blocked by maintainer-built arch:all
+    /// binaries.
+    #[clap(long)]
+    pub source_only_mbox: Option<PathBuf>,
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -225,6 +578,38 @@ pub enum DrtToolsCommands {
     /// packages and or maintainers.
     #[clap(name = "grep-excuses")]
     GrepExcuses(GrepExcusesOptions),
+    /// Report changes in the excuses since the previous download
+    ///
+    /// Whenever the excuses are downloaded, the previous version is kept. This
+    /// command compares both and reports items that migrated, new items, and
+    /// changes in the verdicts of the policies and in the age of the items.
+    #[clap(name = "excuses-diff")]
+    ExcusesDiff(ExcusesDiffOptions),
+    /// Check a hint file for obsolete hints
+    ///
+    /// This command compares the hints with the excuses and the source packages
+    /// in testing and unstable. It reports hints that are already satisfied,
+    /// that are superseded by a newer version in unstable, or that refer to
+    /// packages that no longer exist.
+    #[clap(name = "hints-check")]
+    HintsCheck(HintsCheckOptions),
+    /// Triage unblock requests
+    ///
+    /// This command correlates the unblock requests filed against
+    /// `release.debian.org` with the excuses and the source packages in
+    /// testing and unstable. It lists requests that are ready to be unblocked,
+    /// that are still waiting for age or autopkgtests, that are outdated, or
+    /// that already migrated.
+    #[clap(name = "unblock-requests")]
+    UnblockRequests,
+    /// Report pending autoremovals
+    ///
+    /// This command groups the autoremovals by removal date and sorts them by
+    /// impact. Key packages are listed first, followed by the packages with
+    /// the most reverse dependencies that would be removed with them. Packages
+    /// that are only affected by RC bugs in their dependencies are flagged.
+    #[clap(name = "autoremovals")]
+    AutoRemovals(AutoRemovalsOptions),
     /// Prepare binNMUs to rebuild for outdated Built-Using
     ///
     /// Collect a list of all packages that refer to `Extra-Source-Only: yes`
@@ -3,28 +3,36 @@
 
 use std::{
     borrow::Cow,
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fs::{self, File},
-    io::{BufReader, Write},
+    io::{BufRead, BufReader, Write},
     path::{Path, PathBuf},
+    sync::OnceLock,
+    time::Duration,
 };
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, anyhow};
 use assorted_debian_utils::{
     architectures::Architecture,
-    archive::{Codename, Extension, Suite, SuiteOrCodename},
+    archive::{Codename, Component, Extension, Suite, SuiteOrCodename, WithExtension},
+    distribution::DistributionProfile,
+    excuses::Verdict,
+    package::PackageName,
     release,
 };
-use chrono::DateTime;
+use chrono::NaiveDate;
 use flate2::write::GzDecoder;
 use futures_util::StreamExt;
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
-use log::{debug, trace};
-use reqwest::{Client, Response, StatusCode, header};
+use log::{debug, trace, warn};
+use reqwest::{Certificate, Client, Identity, Proxy, Response, StatusCode, header};
+use serde::Deserialize;
 use tokio::task::JoinSet;
 use xdg::BaseDirectories;
 use xz2::write::XzDecoder;
 
+use crate::cli::{FreezePhase, HttpOptions};
+
 pub(crate) fn default_progress_style() -> ProgressStyle {
     ProgressStyle::default_bar().progress_chars("█  ")
 }
@@ -38,12 +46,60 @@ pub(crate) enum CacheEntries {
     Excuses,
     Packages(SuiteOrCodename),
     Sources(SuiteOrCodename),
+    ComponentSources(SuiteOrCodename, Component),
+    ComponentPackages(SuiteOrCodename, Component),
     FTBFSBugs(SuiteOrCodename),
+    UnblockRequests,
     AutoRemovals,
+    KeyPackages,
     Release(SuiteOrCodename),
+    PortsRelease(SuiteOrCodename),
+}
+
+/// Components of the archive besides main
+pub(crate) const NON_MAIN_COMPONENTS: [Component; 3] = [
+    Component::Contrib,
+    Component::NonFree,
+    Component::NonFreeFirmware,
+];
+
+impl CacheEntries {
+    /// Sources files of all components of a suite
+    pub fn all_sources(suite: SuiteOrCodename) -> Vec<Self> {
+        let mut entries = vec![Self::Sources(suite)];
+        entries.extend(
+            NON_MAIN_COMPONENTS
+                .into_iter()
+                .map(|component| Self::ComponentSources(suite, component)),
+        );
+        entries
+    }
+
+    /// Name of the data if it is only available in its current state and not from snapshots
+    fn live_data(&self) -> Option<&'static str> {
+        match self {
+            Self::Excuses => Some("excuses"),
+            Self::FTBFSBugs(_) => Some("FTBFS bugs"),
+            Self::UnblockRequests => Some("unblock requests"),
+            Self::AutoRemovals => Some("autoremovals"),
+            Self::KeyPackages => Some("key packages"),
+            _ => None,
+        }
+    }
+
+    /// Suite whose Release file is required to download the entry
+    fn release_suite(&self) -> Option<SuiteOrCodename> {
+        match self {
+            Self::Packages(suite)
+            | Self::Sources(suite)
+            | Self::ComponentSources(suite, _)
+            | Self::ComponentPackages(suite, _) => Some(*suite),
+            _ => None,
+        }
+    }
 }
 
-#[derive(Clone, Copy, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub(crate) enum CacheState {
     NoUpdate,
     FreshFiles,
@@ -61,6 +117,8 @@ struct DownloadInfo {
     url: Cow<'static, str>,
     destination: Cow<'static, str>,
     compressor: Compressor,
+    /// Keep the replaced file under this name
+    previous: Option<Cow<'static, str>>,
 }
 
 impl DownloadInfo {
@@ -69,10 +127,467 @@ impl DownloadInfo {
             url,
             destination,
             compressor: Compressor::None,
+            previous: None,
+        }
+    }
+}
+
+/// Default User-Agent for HTTP requests
+const DEFAULT_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+/// Default timeout to establish a connection
+const DEFAULT_CONNECT_TIMEOUT: u64 = 30;
+/// Default timeout for stalled downloads
+const DEFAULT_READ_TIMEOUT: u64 = 120;
+
+/// Settings from the configuration file
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct Settings {
+    /// Settings for HTTP downloads
+    #[serde(default)]
+    pub http: HttpSettings,
+    /// Settings for the freeze
+    #[serde(default)]
+    pub freeze: FreezeSettings,
+    /// Heuristics to select rebuild candidates
+    #[serde(default)]
+    pub rebuild: RebuildConfig,
+}
+
+impl Settings {
+    /// Load settings from the given configuration file or the default location
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => match BaseDirectories::with_prefix("Debian-RT-tools")
+                .find_config_file("config.yaml")
+            {
+                Some(path) => path,
+                None => return Ok(Self::default()),
+            },
+        };
+
+        debug!("Loading configuration from {path:?}");
+        serde_yaml::from_reader(BufReader::new(
+            File::open(&path).with_context(|| format!("Failed to open {path:?}"))?,
+        ))
+        .with_context(|| format!("Failed to parse configuration from {path:?}"))
+    }
+}
+
+/// Settings for the freeze
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub(crate) struct FreezeSettings {
+    /// Start of the transition and toolchain freeze
+    pub transition: Option<NaiveDate>,
+    /// Start of the soft freeze
+    pub soft: Option<NaiveDate>,
+    /// Start of the hard freeze
+    pub hard: Option<NaiveDate>,
+    /// Start of the full freeze
+    pub full: Option<NaiveDate>,
+    /// File with the list of key packages
+    ///
+    /// If not set, the list of key packages is downloaded from UDD.
+    pub key_packages: Option<PathBuf>,
+    /// Minimal age of packages during the soft freeze
+    pub soft_age: u32,
+    /// Minimal age of packages during the hard freeze
+    pub hard_age: u32,
+}
+
+impl Default for FreezeSettings {
+    fn default() -> Self {
+        Self {
+            transition: None,
+            soft: None,
+            hard: None,
+            full: None,
+            key_packages: None,
+            soft_age: 10,
+            hard_age: 20,
+        }
+    }
+}
+
+impl FreezeSettings {
+    /// Determine the phase of the freeze at the given date
+    pub fn phase(&self, date: NaiveDate) -> FreezePhase {
+        [
+            (self.full, FreezePhase::Full),
+            (self.hard, FreezePhase::Hard),
+            (self.soft, FreezePhase::Soft),
+            (self.transition, FreezePhase::Transition),
+        ]
+        .into_iter()
+        .find_map(|(start, phase)| start.is_some_and(|start| start <= date).then_some(phase))
+        .unwrap_or_default()
+    }
+
+    /// Minimal age of packages in the given phase
+    pub fn age_requirement(&self, phase: FreezePhase) -> Option<u32> {
+        match phase {
+            FreezePhase::Soft => Some(self.soft_age),
+            FreezePhase::Hard => Some(self.hard_age),
+            _ => None,
+        }
+    }
+
+    /// Download of the list of key packages required if no local file is configured
+    pub fn key_packages_download(&self) -> Option<CacheEntries> {
+        self.key_packages
+            .is_none()
+            .then_some(CacheEntries::KeyPackages)
+    }
+
+    /// Load the list of key packages
+    ///
+    /// The list is read from the configured file or otherwise from the list downloaded from UDD.
+    /// In the local file, empty lines and lines starting with `#` are ignored.
+    pub fn load_key_packages(&self, cache: &Cache) -> Result<HashSet<PackageName>> {
+        if let Some(path) = &self.key_packages {
+            let file =
+                File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+            return read_key_packages(BufReader::new(file))
+                .with_context(|| format!("Failed to read key packages from {}", path.display()));
+        }
+
+        match cache.get_cache_bufreader(KEY_PACKAGES_FILE) {
+            Ok(reader) => read_udd_key_packages(reader)
+                .with_context(|| format!("Failed to read key packages from {KEY_PACKAGES_FILE}")),
+            Err(err) => {
+                warn!("No list of key packages available: {err:#}");
+                Ok(HashSet::new())
+            }
+        }
+    }
+}
+
+/// Name of the list of key packages downloaded from UDD
+const KEY_PACKAGES_FILE: &str = "udd-key-packages.yaml";
+
+/// Key package as listed by UDD
+#[derive(Debug, Deserialize)]
+struct UddKeyPackage {
+    source: PackageName,
+}
+
+fn read_udd_key_packages<R>(reader: R) -> Result<HashSet<PackageName>>
+where
+    R: BufRead,
+{
+    let key_packages: Vec<UddKeyPackage> = serde_yaml::from_reader(reader)?;
+    Ok(key_packages
+        .into_iter()
+        .map(|key_package| key_package.source)
+        .collect())
+}
+
+fn read_key_packages<R>(reader: R) -> Result<HashSet<PackageName>>
+where
+    R: BufRead,
+{
+    let mut key_packages = HashSet::new();
+    for line in reader.lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        key_packages.insert(
+            PackageName::try_from(line).with_context(|| format!("Invalid package name {line}"))?,
+        );
+    }
+    Ok(key_packages)
+}
+
+/// Minimal age of packages before rebuilding
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(try_from = "AgeThresholdValue")]
+pub(crate) enum AgeThreshold {
+    /// Absolute number of days
+    Days(u32),
+    /// Fraction of the age requirement
+    Fraction(f64),
+}
+
+impl Default for AgeThreshold {
+    fn default() -> Self {
+        Self::Fraction(0.5)
+    }
+}
+
+impl AgeThreshold {
+    /// Compute the minimal age for the given age requirement
+    pub fn minimal_age(self, age_requirement: u32) -> u32 {
+        match self {
+            Self::Days(days) => days,
+            Self::Fraction(fraction) => (f64::from(age_requirement) * fraction).floor() as u32,
+        }
+    }
+}
+
+/// Age thresholds as specified in the configuration file, e.g., `3`, `0.5` or `50%`
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AgeThresholdValue {
+    Days(u32),
+    Fraction(f64),
+    Text(String),
+}
+
+impl TryFrom<AgeThresholdValue> for AgeThreshold {
+    type Error = String;
+
+    fn try_from(value: AgeThresholdValue) -> Result<Self, Self::Error> {
+        let fraction = |fraction: f64| {
+            if (0.0..=1.0).contains(&fraction) {
+                Ok(Self::Fraction(fraction))
+            } else {
+                Err(format!("invalid age fraction {fraction}, expected 0 to 1"))
+            }
+        };
+
+        match value {
+            AgeThresholdValue::Days(days) => Ok(Self::Days(days)),
+            AgeThresholdValue::Fraction(value) => fraction(value),
+            AgeThresholdValue::Text(text) => {
+                if let Some(percent) = text.trim().strip_suffix('%') {
+                    percent
+                        .trim()
+                        .parse::<f64>()
+                        .map_err(|_| format!("invalid age threshold {text}"))
+                        .and_then(|percent| fraction(percent / 100.0))
+                } else {
+                    text.trim()
+                        .parse()
+                        .map(Self::Days)
+                        .map_err(|_| format!("invalid age threshold {text}"))
+                }
+            }
+        }
+    }
+}
+
+/// Urgency of uploads
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Urgency {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl Urgency {
+    /// Derive the urgency from the age requirement using britney's default minimal ages
+    ///
+    /// The excuses only contain the effective age requirement. If britney lowered it, e.g., due to
+    /// passing autopkgtests, the upload is mapped to a higher urgency or to none at all.
+    pub fn from_age_requirement(age_requirement: u32) -> Option<Self> {
+        match age_requirement {
+            10 => Some(Self::Low),
+            5 => Some(Self::Medium),
+            2 => Some(Self::High),
+            0 => Some(Self::Critical),
+            _ => None,
+        }
+    }
+}
+
+/// Heuristics to select rebuild candidates
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub(crate) struct RebuildSettings {
+    /// Minimal age before rebuilding
+    pub age: AgeThreshold,
+    /// Minimal age before rebuilding per urgency
+    ///
+    /// The urgency is guessed from the effective age requirement in the excuses. As britney lowers
+    /// the age requirement for passing autopkgtests, a medium urgency upload whose requirement was
+    /// cut to 2 days is treated as high urgency.
+    pub urgency: HashMap<Urgency, AgeThreshold>,
+    /// Accepted verdicts of the autopkgtest policy
+    pub autopkgtest_verdicts: Vec<Verdict>,
+    /// Policies whose verdicts are ignored
+    pub ignore_policies: Vec<String>,
+}
+
+impl Default for RebuildSettings {
+    fn default() -> Self {
+        Self {
+            age: AgeThreshold::default(),
+            urgency: HashMap::new(),
+            autopkgtest_verdicts: vec![Verdict::Pass],
+            ignore_policies: Vec::new(),
+        }
+    }
+}
+
+impl RebuildSettings {
+    /// Minimal age before rebuilding for the given age requirement
+    pub fn minimal_age(&self, age_requirement: u32) -> u32 {
+        Urgency::from_age_requirement(age_requirement)
+            .and_then(|urgency| self.urgency.get(&urgency))
+            .unwrap_or(&self.age)
+            .minimal_age(age_requirement)
+    }
+
+    /// Check if the verdict of a policy is ignored
+    pub fn is_ignored(&self, policy: &str) -> bool {
+        self.ignore_policies.iter().any(|ignored| ignored == policy)
+    }
+}
+
+/// Heuristics to select rebuild candidates with overrides per phase of the freeze
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(from = "RebuildConfigValue")]
+pub(crate) struct RebuildConfig {
+    /// Default heuristics
+    pub default: RebuildSettings,
+    /// Heuristics during a phase of the freeze
+    pub phases: HashMap<FreezePhase, RebuildSettings>,
+}
+
+impl RebuildConfig {
+    /// Heuristics for the given phase of the freeze
+    pub fn for_phase(&self, phase: FreezePhase) -> &RebuildSettings {
+        self.phases.get(&phase).unwrap_or(&self.default)
+    }
+}
+
+/// Overrides of the heuristics for a phase of the freeze
+///
+/// Fields that are not set are taken from the defaults.
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct RebuildOverrides {
+    age: Option<AgeThreshold>,
+    urgency: Option<HashMap<Urgency, AgeThreshold>>,
+    autopkgtest_verdicts: Option<Vec<Verdict>>,
+    ignore_policies: Option<Vec<String>>,
+}
+
+impl RebuildOverrides {
+    fn apply(self, settings: &RebuildSettings) -> RebuildSettings {
+        RebuildSettings {
+            age: self.age.unwrap_or(settings.age),
+            urgency: self.urgency.unwrap_or_else(|| settings.urgency.clone()),
+            autopkgtest_verdicts: self
+                .autopkgtest_verdicts
+                .unwrap_or_else(|| settings.autopkgtest_verdicts.clone()),
+            ignore_policies: self
+                .ignore_policies
+                .unwrap_or_else(|| settings.ignore_policies.clone()),
+        }
+    }
+}
+
+/// Heuristics as specified in the configuration file
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct RebuildConfigValue {
+    #[serde(flatten)]
+    default: RebuildSettings,
+    #[serde(default)]
+    phases: HashMap<FreezePhase, RebuildOverrides>,
+}
+
+impl From<RebuildConfigValue> for RebuildConfig {
+    fn from(value: RebuildConfigValue) -> Self {
+        let phases = value
+            .phases
+            .into_iter()
+            .map(|(phase, overrides)| (phase, overrides.apply(&value.default)))
+            .collect();
+        Self {
+            default: value.default,
+            phases,
         }
     }
 }
 
+/// Settings for HTTP downloads
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct HttpSettings {
+    /// Proxy for all requests
+    pub proxy: Option<String>,
+    /// Files with additional CA certificates
+    #[serde(default)]
+    pub ca_certificates: Vec<PathBuf>,
+    /// File with client certificate and private key
+    pub client_identity: Option<PathBuf>,
+    /// Timeout in seconds to establish a connection
+    pub connect_timeout: Option<u64>,
+    /// Timeout in seconds for stalled downloads
+    pub read_timeout: Option<u64>,
+    /// User-Agent for HTTP requests
+    pub user_agent: Option<String>,
+}
+
+impl HttpSettings {
+    /// Override settings with options from the command line
+    pub fn with_options(mut self, options: &HttpOptions) -> Self {
+        if options.proxy.is_some() {
+            self.proxy.clone_from(&options.proxy);
+        }
+        self.ca_certificates
+            .extend(options.ca_certificates.iter().cloned());
+        if options.client_identity.is_some() {
+            self.client_identity.clone_from(&options.client_identity);
+        }
+        self.connect_timeout = options.connect_timeout.or(self.connect_timeout);
+        self.read_timeout = options.read_timeout.or(self.read_timeout);
+        if options.user_agent.is_some() {
+            self.user_agent.clone_from(&options.user_agent);
+        }
+        self
+    }
+
+    fn build_client(&self) -> Result<Client> {
+        let mut builder = Client::builder()
+            .user_agent(self.user_agent.as_deref().unwrap_or(DEFAULT_USER_AGENT))
+            .connect_timeout(Duration::from_secs(
+                self.connect_timeout.unwrap_or(DEFAULT_CONNECT_TIMEOUT),
+            ))
+            .read_timeout(Duration::from_secs(
+                self.read_timeout.unwrap_or(DEFAULT_READ_TIMEOUT),
+            ));
+        if let Some(proxy) = &self.proxy {
+            builder = builder
+                .proxy(Proxy::all(proxy).with_context(|| format!("Invalid proxy '{proxy}'"))?);
+        }
+        for path in &self.ca_certificates {
+            let pem = fs::read(path).with_context(|| format!("Failed to read {path:?}"))?;
+            builder =
+                builder
+                    .tls_certs_merge(Certificate::from_pem_bundle(&pem).with_context(|| {
+                        format!("Failed to load CA certificates from {path:?}")
+                    })?);
+        }
+        if let Some(path) = &self.client_identity {
+            let pem = fs::read(path).with_context(|| format!("Failed to read {path:?}"))?;
+            builder =
+                builder
+                    .identity(Identity::from_pem(&pem).with_context(|| {
+                        format!("Failed to load client identity from {path:?}")
+                    })?);
+        }
+        builder.build().context("Failed to create HTTP client")
+    }
+}
+
+/// Turn request errors into errors with a clear message for timeouts
+fn request_error(err: reqwest::Error, url: &str) -> anyhow::Error {
+    if err.is_timeout() {
+        anyhow::Error::new(err).context(format!("Timed out while downloading '{url}'"))
+    } else {
+        anyhow::Error::new(err).context(format!("Failed to GET from '{url}'"))
+    }
+}
+
 #[derive(Clone)]
 struct Downloader {
     always_download: bool,
@@ -80,11 +595,11 @@ struct Downloader {
 }
 
 impl Downloader {
-    pub fn new(always_download: bool) -> Self {
-        Self {
+    pub fn new(always_download: bool, http_settings: &HttpSettings) -> Result<Self> {
+        Ok(Self {
             always_download,
-            client: Client::new(),
-        }
+            client: http_settings.build_client()?,
+        })
     }
 
     async fn download_init(
@@ -109,7 +624,7 @@ impl Downloader {
         .send()
         .await
         .and_then(Response::error_for_status)
-        .with_context(|| format!("Failed to GET from '{}'", &url))?;
+        .map_err(|err| request_error(err, url))?;
 
         if !self.always_download && res.status() == StatusCode::NOT_MODIFIED {
             // this will only trigger if always_download is not set and the server reports that the
@@ -136,9 +651,10 @@ impl Downloader {
         pb: &ProgressBar,
         writer: &mut impl Write,
     ) -> Result<()> {
+        let url = res.url().to_string();
         let mut stream = res.bytes_stream();
         while let Some(item) = stream.next().await {
-            let chunk = item.with_context(|| "Error while downloading file")?;
+            let chunk = item.map_err(|err| request_error(err, &url))?;
             writer
                 .write_all(&chunk)
                 .with_context(|| "Error while writing to file")?;
@@ -151,13 +667,14 @@ impl Downloader {
         &self,
         url: &str,
         path: P,
+        previous: Option<PathBuf>,
         compressor: Compressor,
         mp: MultiProgress,
     ) -> Result<CacheState>
     where
         P: AsRef<Path>,
     {
-        self._download_file(url, path.as_ref(), compressor, mp)
+        self._download_file(url, path.as_ref(), previous.as_deref(), compressor, mp)
             .await
     }
 
@@ -165,6 +682,7 @@ impl Downloader {
         &self,
         url: &str,
         path: &Path,
+        previous: Option<&Path>,
         compressor: Compressor,
         mp: MultiProgress,
     ) -> Result<CacheState> {
@@ -192,6 +710,12 @@ impl Downloader {
             self.download_internal(res, &pb, &mut file).await?;
         }
         pb.finish_with_message(format!("Downloaded {url}"));
+        if let Some(previous) = previous
+            && path.exists()
+        {
+            fs::rename(path, previous)
+                .with_context(|| format!("Failed to move '{path:?}' to '{previous:?}'"))?;
+        }
         fs::rename(&tmp_file, path).with_context(|| {
             format!("Failed to move temporary file '{tmp_file:?}' to '{path:?}'")
         })?;
@@ -200,21 +724,32 @@ impl Downloader {
     }
 }
 
+/// Name of the previously downloaded `excuses.yaml`
+pub(crate) const PREVIOUS_EXCUSES: &str = "excuses-previous.yaml";
+
 fn excuses_urls() -> Vec<DownloadInfo> {
     vec![DownloadInfo {
         url: "https://release.debian.org/britney/excuses.yaml.gz".into(),
         compressor: Compressor::Gz,
         destination: "excuses.yaml".into(),
+        previous: Some(PREVIOUS_EXCUSES.into()),
     }]
 }
 
-fn ftbfs_bugs_urls(codename: Codename) -> Vec<DownloadInfo> {
+fn ftbfs_bugs_urls(codename: &str) -> Vec<DownloadInfo> {
     vec![DownloadInfo::new(
         format!("https://udd.debian.org/bugs/?release={codename}&ftbfs=only&merged=ign&done=ign&rc=1&sortby=id&sorto=asc&format=yaml").into(),
         format!("udd-ftbfs-bugs-{codename}.yaml").into()
     )]
 }
 
+fn unblock_requests_urls() -> Vec<DownloadInfo> {
+    vec![DownloadInfo::new(
+        "https://udd.debian.org/bugs/?release=na&merged=ign&done=hide&usertag=release.debian.org@packages.debian.org:unblock&sortby=id&sorto=asc&format=yaml".into(),
+        "udd-unblock-requests.yaml".into(),
+    )]
+}
+
 fn auto_removals_urls() -> Vec<DownloadInfo> {
     vec![DownloadInfo::new(
         "https://udd.debian.org/cgi-bin/autoremovals.yaml.cgi".into(),
@@ -222,21 +757,11 @@ fn auto_removals_urls() -> Vec<DownloadInfo> {
     )]
 }
 
-fn empty_release() -> release::Release {
-    release::Release {
-        origin: String::default(),
-        label: String::default(),
-        suite: Suite::Unstable,
-        codename: Codename::Sid,
-        version: Option::default(),
-        date: DateTime::default(),
-        valid_until: Option::default(),
-        acquire_by_hash: Option::default(),
-        architectures: Vec::default(),
-        components: Vec::default(),
-        description: String::default(),
-        files: HashMap::default(),
-    }
+fn key_packages_urls() -> Vec<DownloadInfo> {
+    vec![DownloadInfo::new(
+        "https://udd.debian.org/cgi-bin/key_packages.yaml.cgi".into(),
+        KEY_PACKAGES_FILE.into(),
+    )]
 }
 
 pub(crate) trait CachePaths {
@@ -255,166 +780,419 @@ pub(crate) trait CachePaths {
 
     fn get_package_paths(&self, suite: SuiteOrCodename, with_all: bool) -> Result<Vec<PathBuf>>;
 
+    fn get_component_package_path(
+        &self,
+        suite: SuiteOrCodename,
+        component: Component,
+        architecture: Architecture,
+    ) -> Result<PathBuf> {
+        let suite: Suite = suite.into();
+        self.get_cache_path(format!("Packages_{suite}_{component}_{architecture}"))
+    }
+
     fn get_source_path(&self, suite: SuiteOrCodename) -> Result<PathBuf> {
         let suite: Suite = suite.into();
         self.get_cache_path(format!("Sources_{suite}"))
     }
+
+    fn get_component_source_path(
+        &self,
+        suite: SuiteOrCodename,
+        component: Component,
+    ) -> Result<PathBuf> {
+        let suite: Suite = suite.into();
+        self.get_cache_path(format!("Sources_{suite}_{component}"))
+    }
+
+    /// Paths of the Sources files of all components of a suite
+    fn get_all_source_paths(&self, suite: SuiteOrCodename) -> Result<Vec<PathBuf>> {
+        let mut paths = vec![self.get_source_path(suite)?];
+        for component in NON_MAIN_COMPONENTS {
+            paths.push(self.get_component_source_path(suite, component)?);
+        }
+        Ok(paths)
+    }
+}
+
+/// Default mirror for the `-security` suites
+const DEFAULT_SECURITY_MIRROR: &str = "https://security.debian.org/debian-security";
+
+/// Suites available from debian-ports
+const PORTS_SUITES: [SuiteOrCodename; 2] =
+    [SuiteOrCodename::UNSTABLE, SuiteOrCodename::EXPERIMENTAL];
+
+/// Mirrors for the archive, its extensions and debian-ports
+#[derive(Debug, Clone)]
+pub(crate) struct Mirrors {
+    archive: String,
+    extensions: HashMap<Extension, String>,
+    ports: Option<String>,
+    snapshot: Option<String>,
+}
+
+impl Mirrors {
+    pub fn new(archive_mirror: &str) -> Self {
+        Self {
+            archive: archive_mirror.into(),
+            extensions: HashMap::from([(Extension::Security, DEFAULT_SECURITY_MIRROR.into())]),
+            ports: None,
+            snapshot: None,
+        }
+    }
+
+    /// Also consider architectures from debian-ports
+    pub fn with_ports_mirror(mut self, mirror: &str) -> Self {
+        self.ports = Some(mirror.into());
+        self
+    }
+
+    /// Override the mirror for an extension
+    pub fn with_extension_mirror(mut self, extension: Extension, mirror: &str) -> Self {
+        self.extensions.insert(extension, mirror.into());
+        self
+    }
+
+    /// Download from the given snapshot instead
+    ///
+    /// The archive, the security archive and debian-ports are taken from the snapshot. Other
+    /// extension mirrors are dropped as the extensions are served from the archive itself.
+    pub fn with_snapshot(self, snapshot_mirror: &str, timestamp: &str) -> Self {
+        for (extension, mirror) in &self.extensions {
+            if *extension != Extension::Security || mirror != DEFAULT_SECURITY_MIRROR {
+                warn!("Ignoring mirror {mirror} for {extension}: using snapshot {timestamp}");
+            }
+        }
+        let snapshot_mirror = snapshot_mirror.trim_end_matches('/');
+        Self {
+            archive: format!("{snapshot_mirror}/archive/debian/{timestamp}"),
+            extensions: HashMap::from([(
+                Extension::Security,
+                format!("{snapshot_mirror}/archive/debian-security/{timestamp}"),
+            )]),
+            ports: self
+                .ports
+                .map(|_| format!("{snapshot_mirror}/archive/debian-ports/{timestamp}")),
+            snapshot: Some(timestamp.into()),
+        }
+    }
+
+    /// Cache directory prefix for downloads from these mirrors
+    fn cache_prefix(&self) -> PathBuf {
+        let prefix = PathBuf::from("Debian-RT-tools");
+        if let Some(snapshot) = &self.snapshot {
+            prefix.join("snapshots").join(snapshot)
+        } else {
+            prefix
+        }
+    }
+
+    /// Mirror serving the given suite
+    fn for_suite(&self, suite: Suite) -> &str {
+        match suite {
+            Suite::Testing(Some(extension))
+            | Suite::Stable(Some(extension))
+            | Suite::OldStable(Some(extension))
+            | Suite::OldOldStable(Some(extension)) => {
+                self.extensions.get(&extension).unwrap_or(&self.archive)
+            }
+            _ => &self.archive,
+        }
+    }
 }
 
 pub(crate) struct Cache {
     base_directory: BaseDirectories,
     downloader: Downloader,
-    archive_mirror: String,
-    unstable: release::Release,
-    testing: release::Release,
-    stable: release::Release,
-    oldstable: release::Release,
-    experimental: release::Release,
-    stable_proposed_updates: release::Release,
-    oldstable_proposed_updates: release::Release,
-    stable_backports: release::Release,
-    // oldstable_backports: release::Release,
+    mirrors: Mirrors,
+    releases: HashMap<Suite, release::Release>,
+    optional_releases: HashMap<Suite, OnceLock<release::Release>>,
+    ports_releases: HashMap<Suite, release::Release>,
+    /// Mapping of suites and codenames as published by the mirror
+    profile: DistributionProfile,
 }
 
 impl Cache {
-    pub async fn new(force_download: bool, archive_mirror: &str) -> Result<Self> {
+    pub async fn new(
+        force_download: bool,
+        mirrors: Mirrors,
+        http_settings: &HttpSettings,
+    ) -> Result<Self> {
         let mut cache = Self {
-            base_directory: BaseDirectories::with_prefix("Debian-RT-tools"),
-            downloader: Downloader::new(force_download),
-            archive_mirror: archive_mirror.into(),
-            unstable: empty_release(),
-            testing: empty_release(),
-            stable: empty_release(),
-            oldstable: empty_release(),
-            experimental: empty_release(),
-            stable_proposed_updates: empty_release(),
-            oldstable_proposed_updates: empty_release(),
-            stable_backports: empty_release(),
-            // oldstable_backports: empty_release(),
+            base_directory: BaseDirectories::with_prefix(mirrors.cache_prefix()),
+            downloader: Downloader::new(force_download, http_settings)?,
+            mirrors,
+            releases: HashMap::default(),
+            // oldoldstable and the security archives might not be available from every mirror, so
+            // their Release files are only downloaded once a command requires them
+            optional_releases: [
+                SuiteOrCodename::OLDOLDSTABLE,
+                SuiteOrCodename::STABLE_SECURITY,
+                SuiteOrCodename::OLDSTABLE_SECURITY,
+            ]
+            .into_iter()
+            .map(|suite| (suite.into(), OnceLock::new()))
+            .collect(),
+            ports_releases: HashMap::default(),
+            profile: DistributionProfile::debian(),
         };
 
-        // download Release files for unstable, testing and stable
+        // download Release files for unstable, testing, stable and oldstable
+        let releases = [
+            SuiteOrCodename::UNSTABLE,
+            SuiteOrCodename::TESTING,
+            SuiteOrCodename::STABLE,
+            SuiteOrCodename::OLDSTABLE,
+            SuiteOrCodename::EXPERIMENTAL,
+            SuiteOrCodename::STABLE_PU,
+            SuiteOrCodename::OLDSTABLE_PU,
+            SuiteOrCodename::STABLE_BACKPORTS,
+            // SuiteOrCodename::Suite(Suite::OldStable(Some(Extension::Backports))),
+        ];
         cache
-            .download(&[
-                CacheEntries::Release(SuiteOrCodename::UNSTABLE),
-                CacheEntries::Release(SuiteOrCodename::TESTING),
-                CacheEntries::Release(SuiteOrCodename::STABLE),
-                CacheEntries::Release(SuiteOrCodename::OLDSTABLE),
-                CacheEntries::Release(SuiteOrCodename::EXPERIMENTAL),
-                CacheEntries::Release(SuiteOrCodename::STABLE_PU),
-                CacheEntries::Release(SuiteOrCodename::OLDSTABLE_PU),
-                CacheEntries::Release(SuiteOrCodename::STABLE_BACKPORTS),
-                // CacheEntries::Release(Suite::OldStable(Some(Extension::Backports))),
-            ])
+            .download_entries(&releases.map(CacheEntries::Release))
             .await?;
+        for suite in releases {
+            let suite = suite.into();
+            let release = cache.read_release(suite)?;
+            cache.releases.insert(suite, release);
+            if suite == suite.without_extension() {
+                cache
+                    .profile
+                    .add_release(cache.get_cache_bufreader(format!("Release_{suite}"))?)
+                    .with_context(|| format!("Failed to read suite and codename of {suite}"))?;
+            }
+        }
 
-        cache.unstable = release::from_reader(
-            cache.get_cache_bufreader(format!("Release_{}", Suite::Unstable))?,
-        )?;
-        cache.testing = release::from_reader(
-            cache.get_cache_bufreader(format!("Release_{}", Suite::Testing(None)))?,
-        )?;
-        cache.stable = release::from_reader(
-            cache.get_cache_bufreader(format!("Release_{}", Suite::Stable(None)))?,
-        )?;
-        cache.oldstable = release::from_reader(
-            cache.get_cache_bufreader(format!("Release_{}", Suite::OldStable(None)))?,
-        )?;
-        cache.experimental = release::from_reader(
-            cache.get_cache_bufreader(format!("Release_{}", Suite::Experimental))?,
-        )?;
-        cache.stable_proposed_updates =
-            release::from_reader(cache.get_cache_bufreader(format!(
-                "Release_{}",
-                Suite::Stable(Some(Extension::ProposedUpdates))
-            ))?)?;
-        cache.oldstable_proposed_updates =
-            release::from_reader(cache.get_cache_bufreader(format!(
-                "Release_{}",
-                Suite::OldStable(Some(Extension::ProposedUpdates))
-            ))?)?;
-        cache.stable_backports = release::from_reader(cache.get_cache_bufreader(format!(
-            "Release_{}",
-            Suite::Stable(Some(Extension::Backports))
-        ))?)?;
-        // cache.oldstable_backports = release::from_reader(cache.get_cache_bufreader(format!(
-        //     "Release_{}",
-        //     Suite::OldStable(Some(Extension::Backports))
-        // ))?)?;
+        if cache.mirrors.ports.is_some() {
+            cache
+                .download_entries(&PORTS_SUITES.map(CacheEntries::PortsRelease))
+                .await?;
+            for suite in PORTS_SUITES {
+                cache.load_ports_release(suite.into())?;
+            }
+        }
 
         Ok(cache)
     }
 
+    /// Codename of a suite according to the Release files of the mirror
+    pub fn codename(&self, suite: SuiteOrCodename) -> String {
+        let suite = Suite::from(suite);
+        self.profile
+            .codename_for_suite(&suite.to_string())
+            .unwrap_or_else(|| Codename::from(suite).to_string())
+    }
+
+    fn read_release(&self, suite: Suite) -> Result<release::Release> {
+        release::from_reader(self.get_cache_bufreader(format!("Release_{suite}"))?)
+            .with_context(|| format!("Failed to parse Release file for {suite}"))
+    }
+
+    /// Download and load the optional Release files required by the given entries
+    async fn load_optional_releases(&self, entries: &[CacheEntries]) -> Result<()> {
+        let missing: HashSet<Suite> = entries
+            .iter()
+            .filter_map(CacheEntries::release_suite)
+            .map(Suite::from)
+            .filter(|suite| {
+                self.optional_releases
+                    .get(suite)
+                    .is_some_and(|release| release.get().is_none())
+            })
+            .collect();
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        let entries: Vec<_> = missing
+            .iter()
+            .map(|suite| CacheEntries::Release((*suite).into()))
+            .collect();
+        self.download_entries(&entries).await?;
+        for suite in missing {
+            let release = self.read_release(suite)?;
+            // another download might have loaded the same Release file in the meantime
+            let _ = self.optional_releases[&suite].set(release);
+        }
+        Ok(())
+    }
+
+    fn load_ports_release(&mut self, suite: Suite) -> Result<()> {
+        let release =
+            release::from_reader(self.get_cache_bufreader(format!("Release-ports_{suite}"))?)
+                .with_context(|| {
+                    format!("Failed to parse debian-ports Release file for {suite}")
+                })?;
+        self.ports_releases.insert(suite, release);
+        Ok(())
+    }
+
+    fn release(&self, suite: Suite) -> Result<&release::Release> {
+        self.releases
+            .get(&suite)
+            .or_else(|| self.optional_releases.get(&suite).and_then(OnceLock::get))
+            .with_context(|| format!("Release file for {suite} is not available"))
+    }
+
+    /// Mirror and Release file providing the given architecture
+    ///
+    /// Architectures from the official archive take precedence over those from debian-ports.
+    fn release_for_architecture(
+        &self,
+        suite: Suite,
+        architecture: Architecture,
+    ) -> Result<(&str, &release::Release)> {
+        let release = self.release(suite)?;
+        if release.architectures.contains(&architecture) {
+            return Ok((self.mirrors.for_suite(suite), release));
+        }
+        if let Some(ports_mirror) = &self.mirrors.ports
+            && let Some(ports_release) = self.ports_releases.get(&suite)
+            && ports_release.architectures.contains(&architecture)
+        {
+            return Ok((ports_mirror, ports_release));
+        }
+        Err(anyhow!("{architecture} is not available in {suite}"))
+    }
+
     /// Lookup URL based on information from Release files
-    fn lookup_url(&self, suite: Suite, path: &str) -> String {
-        format!(
-            "{}/dists/{suite}/{}",
-            self.archive_mirror,
-            match suite {
-                Suite::Unstable => &self.unstable,
-                Suite::Testing(_) => &self.testing,
-                Suite::Stable(None) => &self.stable,
-                Suite::OldStable(None) => &self.oldstable,
-                Suite::Experimental => &self.experimental,
-                Suite::Stable(Some(Extension::ProposedUpdates)) => &self.stable_proposed_updates,
-                Suite::OldStable(Some(Extension::ProposedUpdates)) =>
-                    &self.oldstable_proposed_updates,
-                Suite::Stable(Some(Extension::Backports)) => &self.stable_backports,
-                // Suite::OldStable(Some(Extension::Backports)) => &self.oldstable_backports,
-                _ => unreachable!("Suite {} is currently not handled.", suite),
-            }
+    fn lookup_url(&self, suite: Suite, path: &str) -> Result<String> {
+        let url = self
+            .release(suite)?
             .lookup_url(path)
-            .expect("file needs to be available")
-        )
+            .with_context(|| format!("{path} is not available in {suite}"))?;
+        Ok(format!(
+            "{}/dists/{suite}/{url}",
+            self.mirrors.for_suite(suite)
+        ))
     }
 
-    fn packages_urls(&self, suite: Suite) -> Vec<DownloadInfo> {
-        self.architectures_for_suite(suite)
-            .iter()
-            .map(|architecture| DownloadInfo {
-                url: self
-                    .lookup_url(suite, &format!("main/binary-{architecture}/Packages.xz"))
-                    .into(),
-                compressor: Compressor::Xz,
-                destination: format!("Packages_{suite}_{architecture}").into(),
+    fn packages_urls(&self, suite: Suite) -> Result<Vec<DownloadInfo>> {
+        self.architectures_for_suite(suite)?
+            .into_iter()
+            .map(|architecture| {
+                let (mirror, release) = self.release_for_architecture(suite, architecture)?;
+                let path = format!("main/binary-{architecture}/Packages.xz");
+                let url = release
+                    .lookup_url(&path)
+                    .with_context(|| format!("{path} is not available in {suite}"))?;
+                Ok(DownloadInfo {
+                    url: format!("{mirror}/dists/{suite}/{url}").into(),
+                    compressor: Compressor::Xz,
+                    destination: format!("Packages_{suite}_{architecture}").into(),
+                    previous: None,
+                })
             })
             .collect()
     }
 
-    fn source_urls(&self, suite: Suite) -> Vec<DownloadInfo> {
-        vec![DownloadInfo {
-            url: self.lookup_url(suite, "main/source/Sources.xz").into(),
+    fn source_urls(&self, suite: Suite) -> Result<Vec<DownloadInfo>> {
+        Ok(vec![DownloadInfo {
+            url: self.lookup_url(suite, "main/source/Sources.xz")?.into(),
             compressor: Compressor::Xz,
             destination: format!("Sources_{suite}").into(),
-        }]
+            previous: None,
+        }])
+    }
+
+    fn component_source_urls(
+        &self,
+        suite: Suite,
+        component: Component,
+    ) -> Result<Vec<DownloadInfo>> {
+        Ok(vec![DownloadInfo {
+            url: self
+                .lookup_url(suite, &format!("{component}/source/Sources.xz"))?
+                .into(),
+            compressor: Compressor::Xz,
+            destination: format!("Sources_{suite}_{component}").into(),
+            previous: None,
+        }])
+    }
+
+    /// Packages files of a component
+    ///
+    /// Only architectures from the official archive are considered.
+    fn component_packages_urls(
+        &self,
+        suite: Suite,
+        component: Component,
+    ) -> Result<Vec<DownloadInfo>> {
+        self.release(suite)?
+            .architectures
+            .iter()
+            .map(|architecture| {
+                Ok(DownloadInfo {
+                    url: self
+                        .lookup_url(
+                            suite,
+                            &format!("{component}/binary-{architecture}/Packages.xz"),
+                        )?
+                        .into(),
+                    compressor: Compressor::Xz,
+                    destination: format!("Packages_{suite}_{component}_{architecture}").into(),
+                    previous: None,
+                })
+            })
+            .collect()
     }
 
     fn release_urls(&self, suite: Suite) -> Vec<DownloadInfo> {
         vec![DownloadInfo::new(
-            format!("{}/dists/{suite}/Release", self.archive_mirror,).into(),
+            format!("{}/dists/{suite}/Release", self.mirrors.for_suite(suite)).into(),
             format!("Release_{suite}").into(),
         )]
     }
 
-    fn cache_entries_to_urls_dests(&self, entries: &[CacheEntries]) -> Vec<DownloadInfo> {
-        entries
+    fn ports_release_urls(&self, suite: Suite) -> Vec<DownloadInfo> {
+        self.mirrors
+            .ports
             .iter()
-            .flat_map(|entry| {
-                match entry {
-                    CacheEntries::Excuses => excuses_urls(),
-                    CacheEntries::Packages(suite) => self.packages_urls((*suite).into()),
-                    CacheEntries::Sources(suite) => self.source_urls((*suite).into()),
-                    CacheEntries::FTBFSBugs(codename) => ftbfs_bugs_urls((*codename).into()),
-                    CacheEntries::AutoRemovals => auto_removals_urls(),
-                    CacheEntries::Release(suite) => self.release_urls((*suite).into()),
-                }
-                .into_iter()
+            .map(|mirror| {
+                DownloadInfo::new(
+                    format!("{mirror}/dists/{suite}/Release").into(),
+                    format!("Release-ports_{suite}").into(),
+                )
             })
             .collect()
     }
 
+    fn cache_entries_to_urls_dests(&self, entries: &[CacheEntries]) -> Result<Vec<DownloadInfo>> {
+        let mut urls_and_dests = vec![];
+        for entry in entries {
+            if let Some(snapshot) = &self.mirrors.snapshot
+                && let Some(name) = entry.live_data()
+            {
+                warn!("The {name} are not available from snapshot {snapshot}: using current data");
+            }
+            urls_and_dests.extend(match entry {
+                CacheEntries::Excuses => excuses_urls(),
+                CacheEntries::Packages(suite) => self.packages_urls((*suite).into())?,
+                CacheEntries::Sources(suite) => self.source_urls((*suite).into())?,
+                CacheEntries::ComponentSources(suite, component) => {
+                    self.component_source_urls((*suite).into(), *component)?
+                }
+                CacheEntries::ComponentPackages(suite, component) => {
+                    self.component_packages_urls((*suite).into(), *component)?
+                }
+                CacheEntries::FTBFSBugs(suite) => ftbfs_bugs_urls(&self.codename(*suite)),
+                CacheEntries::UnblockRequests => unblock_requests_urls(),
+                CacheEntries::AutoRemovals => auto_removals_urls(),
+                CacheEntries::KeyPackages => key_packages_urls(),
+                CacheEntries::Release(suite) => self.release_urls((*suite).into()),
+                CacheEntries::PortsRelease(suite) => self.ports_release_urls((*suite).into()),
+            });
+        }
+        Ok(urls_and_dests)
+    }
+
     pub async fn download(&self, entries: &[CacheEntries]) -> Result<CacheState> {
-        let urls_and_dests = self.cache_entries_to_urls_dests(entries);
+        self.load_optional_releases(entries).await?;
+        self.download_entries(entries).await
+    }
+
+    async fn download_entries(&self, entries: &[CacheEntries]) -> Result<CacheState> {
+        let urls_and_dests = self.cache_entries_to_urls_dests(entries)?;
         trace!(
             "Scheduling {} URLs to download: {:?}",
             urls_and_dests.len(),
@@ -425,12 +1203,23 @@ impl Cache {
         let mut join_handles = JoinSet::new();
         for download_info in urls_and_dests {
             let dest = self.get_cache_path(download_info.destination.as_ref())?;
+            let previous = download_info
+                .previous
+                .as_ref()
+                .map(|previous| self.get_cache_path(previous.as_ref()))
+                .transpose()?;
             let downloader = self.downloader.clone();
             let mp = mp.clone();
             join_handles.spawn(async move {
                 debug!("Starting task to download {}", download_info.url);
                 downloader
-                    .download_file(&download_info.url, dest, download_info.compressor, mp)
+                    .download_file(
+                        &download_info.url,
+                        dest,
+                        previous,
+                        download_info.compressor,
+                        mp,
+                    )
                     .await
             });
         }
@@ -474,14 +1263,32 @@ impl Cache {
         )?))
     }*/
 
-    // Architectures for a suite (including Arch: all)
-    pub fn architectures_for_suite(&self, suite: Suite) -> &[Architecture] {
-        match suite {
-            Suite::Unstable | Suite::Experimental => &self.unstable.architectures,
-            Suite::Testing(_) => &self.testing.architectures,
-            Suite::Stable(_) => &self.stable.architectures,
-            Suite::OldStable(_) => &self.oldstable.architectures,
-        }
+    /// Paths of the Packages files of a component
+    pub fn get_component_package_paths(
+        &self,
+        suite: SuiteOrCodename,
+        component: Component,
+        with_all: bool,
+    ) -> Result<Vec<PathBuf>> {
+        self.release(suite.into())?
+            .architectures
+            .iter()
+            .filter(|architecture| with_all || **architecture != Architecture::All)
+            .map(|architecture| self.get_component_package_path(suite, component, *architecture))
+            .collect()
+    }
+
+    // Architectures for a suite (including Arch: all and architectures from debian-ports)
+    pub fn architectures_for_suite(&self, suite: Suite) -> Result<Vec<Architecture>> {
+        let release = self
+            .release(suite)
+            .or_else(|_| self.release(suite.without_extension()))?;
+        Ok(combine_architectures(
+            &release.architectures,
+            self.ports_releases
+                .get(&suite)
+                .map(|release| release.architectures.as_slice()),
+        ))
     }
 }
 
@@ -495,17 +1302,31 @@ impl CachePaths for Cache {
 
     fn get_package_paths(&self, suite: SuiteOrCodename, with_all: bool) -> Result<Vec<PathBuf>> {
         let mut all_paths = vec![];
-        for architecture in self.architectures_for_suite(suite.into()) {
-            if !with_all && *architecture == Architecture::All {
+        for architecture in self.architectures_for_suite(suite.into())? {
+            if !with_all && architecture == Architecture::All {
                 continue;
             }
 
-            all_paths.push(self.get_package_path(suite, *architecture)?);
+            all_paths.push(self.get_package_path(suite, architecture)?);
         }
         Ok(all_paths)
     }
 }
 
+/// Combine architectures from the official archive and debian-ports
+fn combine_architectures(
+    architectures: &[Architecture],
+    ports_architectures: Option<&[Architecture]>,
+) -> Vec<Architecture> {
+    let mut architectures = architectures.to_vec();
+    for architecture in ports_architectures.unwrap_or_default() {
+        if !architectures.contains(architecture) {
+            architectures.push(*architecture);
+        }
+    }
+    architectures
+}
+
 /// Check if package should be skipped for binNMUs.
 pub fn source_skip_binnmu(source: &str) -> bool {
     source.starts_with("debian-installer")
@@ -524,6 +1345,310 @@ pub fn source_skip_binnmu(source: &str) -> bool {
 mod test {
     use super::*;
 
+    #[test]
+    fn mirror_for_suite() {
+        let mirrors = Mirrors::new("https://deb.debian.org/debian")
+            .with_extension_mirror(Extension::Backports, "https://backports.example.org");
+
+        assert_eq!(
+            mirrors.for_suite(Suite::Stable(None)),
+            "https://deb.debian.org/debian"
+        );
+        assert_eq!(
+            mirrors.for_suite(Suite::OldStable(Some(Extension::Security))),
+            DEFAULT_SECURITY_MIRROR
+        );
+        assert_eq!(
+            mirrors.for_suite(Suite::Stable(Some(Extension::Backports))),
+            "https://backports.example.org"
+        );
+        assert_eq!(
+            mirrors.for_suite(Suite::Stable(Some(Extension::ProposedUpdates))),
+            "https://deb.debian.org/debian"
+        );
+    }
+
+    #[test]
+    fn ports_architectures() {
+        let official = [
+            Architecture::All,
+            Architecture::Amd64,
+            Architecture::Loong64,
+        ];
+        assert_eq!(combine_architectures(&official, None), official);
+        assert_eq!(
+            combine_architectures(
+                &official,
+                Some(&[
+                    Architecture::Alpha,
+                    Architecture::Loong64,
+                    Architecture::M68k
+                ])
+            ),
+            [
+                Architecture::All,
+                Architecture::Amd64,
+                Architecture::Loong64,
+                Architecture::Alpha,
+                Architecture::M68k
+            ]
+        );
+    }
+
+    #[test]
+    fn snapshot_mirrors() {
+        let mirrors = Mirrors::new("https://deb.debian.org/debian")
+            .with_extension_mirror(Extension::Backports, "https://backports.example.org")
+            .with_ports_mirror("https://deb.debian.org/debian-ports")
+            .with_snapshot("https://snapshot.debian.org/", "20260101T000000Z");
+
+        assert_eq!(
+            mirrors.for_suite(Suite::Unstable),
+            "https://snapshot.debian.org/archive/debian/20260101T000000Z"
+        );
+        assert_eq!(
+            mirrors.for_suite(Suite::Stable(Some(Extension::Backports))),
+            "https://snapshot.debian.org/archive/debian/20260101T000000Z"
+        );
+        assert_eq!(
+            mirrors.for_suite(Suite::Stable(Some(Extension::Security))),
+            "https://snapshot.debian.org/archive/debian-security/20260101T000000Z"
+        );
+        assert_eq!(
+            mirrors.ports.as_deref(),
+            Some("https://snapshot.debian.org/archive/debian-ports/20260101T000000Z")
+        );
+        assert_eq!(
+            mirrors.cache_prefix(),
+            Path::new("Debian-RT-tools/snapshots/20260101T000000Z")
+        );
+        assert_eq!(
+            Mirrors::new("https://deb.debian.org/debian").cache_prefix(),
+            Path::new("Debian-RT-tools")
+        );
+    }
+
+    /// Minimal HTTP server answering a single request with the given response
+    ///
+    /// The server task returns the connection, which is kept open, and the request.
+    async fn serve_once(
+        response: String,
+    ) -> (
+        std::net::SocketAddr,
+        tokio::task::JoinHandle<(tokio::net::TcpStream, String)>,
+    ) {
+        use tokio::{
+            io::{AsyncReadExt, AsyncWriteExt},
+            net::TcpListener,
+        };
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0u8; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                let read = stream.read(&mut buffer).await.unwrap();
+                request.extend_from_slice(&buffer[..read]);
+            }
+            stream.write_all(response.as_bytes()).await.unwrap();
+            (stream, String::from_utf8(request).unwrap())
+        });
+        (address, server)
+    }
+
+    #[tokio::test]
+    async fn download_from_snapshot() {
+        const RELEASE: &str = "Origin: Debian\nSuite: unstable\nCodename: sid\n";
+
+        // minimal stand-in for snapshot.debian.org serving a single file
+        let (address, server) = serve_once(format!(
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{RELEASE}",
+            RELEASE.len()
+        ))
+        .await;
+
+        let mirrors = Mirrors::new("https://deb.debian.org/debian")
+            .with_snapshot(&format!("http://{address}"), "20260101T000000Z");
+        let temp_dir = tempfile::tempdir().unwrap();
+        let destination = temp_dir.path().join("Release_unstable");
+        let previous = temp_dir.path().join("Release_unstable-previous");
+        fs::write(&destination, "Origin: Debian\n").unwrap();
+        let state = Downloader::new(true, &HttpSettings::default())
+            .unwrap()
+            .download_file(
+                &format!(
+                    "{}/dists/unstable/Release",
+                    mirrors.for_suite(Suite::Unstable)
+                ),
+                &destination,
+                Some(previous.clone()),
+                Compressor::None,
+                MultiProgress::new(),
+            )
+            .await
+            .unwrap();
+
+        assert!(state == CacheState::FreshFiles);
+        let (_, request) = server.await.unwrap();
+        assert_eq!(
+            request.lines().next().unwrap(),
+            "GET /archive/debian/20260101T000000Z/dists/unstable/Release HTTP/1.1"
+        );
+        assert_eq!(fs::read_to_string(destination).unwrap(), RELEASE);
+        // the replaced file is kept
+        assert_eq!(fs::read_to_string(previous).unwrap(), "Origin: Debian\n");
+    }
+
+    #[tokio::test]
+    async fn download_timeout() {
+        // stand-in for a mirror that stalls after sending the headers
+        let (address, server) =
+            serve_once("HTTP/1.1 200 OK\r\nContent-Length: 1024\r\n\r\n".into()).await;
+
+        let settings = HttpSettings {
+            read_timeout: Some(1),
+            user_agent: Some("drt-tools-test".into()),
+            ..Default::default()
+        };
+        let temp_dir = tempfile::tempdir().unwrap();
+        let url = format!("http://{address}/dists/unstable/Release");
+        let err = Downloader::new(true, &settings)
+            .unwrap()
+            .download_file(
+                &url,
+                &temp_dir.path().join("Release_unstable"),
+                None,
+                Compressor::None,
+                MultiProgress::new(),
+            )
+            .await
+            .unwrap_err();
+
+        assert!(err.to_string().starts_with("Timed out"));
+        let (_, request) = server.await.unwrap();
+        assert!(request.contains("user-agent: drt-tools-test\r\n"));
+    }
+
+    #[test]
+    fn http_settings() {
+        let settings: Settings = serde_yaml::from_str(
+            r"---
+http:
+  proxy: http://proxy.example.org:3128
+  ca-certificates:
+    - /etc/ssl/local.pem
+  read-timeout: 60
+",
+        )
+        .unwrap();
+        let settings = settings.http.with_options(&HttpOptions {
+            proxy: None,
+            ca_certificates: vec!["/tmp/extra.pem".into()],
+            client_identity: None,
+            connect_timeout: Some(10),
+            read_timeout: None,
+            user_agent: Some("custom/1.0".into()),
+        });
+
+        assert_eq!(
+            settings.proxy.as_deref(),
+            Some("http://proxy.example.org:3128")
+        );
+        assert_eq!(settings.ca_certificates.len(), 2);
+        assert_eq!(settings.connect_timeout, Some(10));
+        assert_eq!(settings.read_timeout, Some(60));
+        assert_eq!(settings.user_agent.as_deref(), Some("custom/1.0"));
+    }
+
+    #[test]
+    fn freeze_settings() {
+        let settings: Settings = serde_yaml::from_str(
+            r"---
+freeze:
+  transition: 2027-01-12
+  soft: 2027-02-12
+  hard: 2027-03-12
+  key-packages: /etc/drt-tools/key-packages
+  hard-age: 30
+",
+        )
+        .unwrap();
+        let date = |date: &str| NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap();
+
+        assert_eq!(settings.freeze.phase(date("2027-01-01")), FreezePhase::None);
+        assert_eq!(
+            settings.freeze.phase(date("2027-01-12")),
+            FreezePhase::Transition
+        );
+        assert_eq!(settings.freeze.phase(date("2027-02-20")), FreezePhase::Soft);
+        assert_eq!(settings.freeze.phase(date("2027-05-01")), FreezePhase::Hard);
+        assert_eq!(
+            Settings::default().freeze.phase(date("2027-05-01")),
+            FreezePhase::None
+        );
+        assert_eq!(settings.freeze.age_requirement(FreezePhase::Soft), Some(10));
+        assert_eq!(settings.freeze.age_requirement(FreezePhase::Hard), Some(30));
+        assert_eq!(settings.freeze.age_requirement(FreezePhase::Full), None);
+
+        let key_packages =
+            read_key_packages("# key packages\nzathura\n\n  girara  \n".as_bytes()).unwrap();
+        assert_eq!(key_packages.len(), 2);
+        assert!(key_packages.contains(&PackageName::try_from("girara").unwrap()));
+        assert!(read_key_packages("not a package\n".as_bytes()).is_err());
+
+        let key_packages =
+            read_udd_key_packages("---\n- source: zathura\n- source: girara\n".as_bytes()).unwrap();
+        assert_eq!(key_packages.len(), 2);
+        assert!(key_packages.contains(&PackageName::try_from("zathura").unwrap()));
+    }
+
+    #[test]
+    fn rebuild_settings() {
+        let settings: Settings = serde_yaml::from_str(
+            r"---
+rebuild:
+  age: 3
+  urgency:
+    high: 100%
+  autopkgtest-verdicts:
+    - PASS
+    - PASS_HINTED
+  ignore-policies:
+    - piuparts
+  phases:
+    hard:
+      age: 0.75
+",
+        )
+        .unwrap();
+
+        let rebuild = settings.rebuild.for_phase(FreezePhase::None);
+        assert_eq!(rebuild.age, AgeThreshold::Days(3));
+        assert_eq!(rebuild.minimal_age(5), 3);
+        assert_eq!(rebuild.minimal_age(2), 2);
+        assert!(rebuild.autopkgtest_verdicts.contains(&Verdict::PassHinted));
+        assert!(rebuild.is_ignored("piuparts"));
+
+        // phases only override the fields they set
+        let rebuild = settings.rebuild.for_phase(FreezePhase::Hard);
+        assert_eq!(rebuild.minimal_age(20), 15);
+        assert!(rebuild.autopkgtest_verdicts.contains(&Verdict::PassHinted));
+        assert!(rebuild.is_ignored("piuparts"));
+
+        // unknown phases and fields are rejected
+        assert!(serde_yaml::from_str::<RebuildConfig>("phases:\n  Hard:\n    age: 1\n").is_err());
+        assert!(
+            serde_yaml::from_str::<RebuildConfig>("phases:\n  hard-freeze:\n    age: 1\n").is_err()
+        );
+        assert!(serde_yaml::from_str::<RebuildConfig>("phases:\n  hard:\n    ag: 1\n").is_err());
+
+        // defaults match the previous heuristic of half the age requirement
+        assert_eq!(RebuildSettings::default().minimal_age(5), 2);
+        assert!(serde_yaml::from_str::<RebuildSettings>("age: 150%").is_err());
+    }
+
     #[test]
     fn skip_binnmu() {
         assert!(source_skip_binnmu("linux-signed-i386"));
@@ -0,0 +1,462 @@
+// Copyright 2026 Sebastian Ramacher
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufRead, BufReader},
+};
+
+use anyhow::{Context, Result};
+use assorted_debian_utils::{
+    archive::SuiteOrCodename,
+    excuses::{ExcusesItem, ExcusesReader, Verdict},
+    package::PackageName,
+    version::PackageVersion,
+};
+use chrono::{DateTime, Utc};
+
+use crate::{
+    Command, Downloads,
+    cli::ExcusesDiffOptions,
+    config::{self, CacheEntries, CachePaths, PREVIOUS_EXCUSES},
+    grep_excuses::Pattern,
+    source_packages::SourcePackages,
+};
+
+pub(crate) struct ExcusesDiff<'a> {
+    cache: &'a config::Cache,
+    options: ExcusesDiffOptions,
+}
+
+impl<'a> ExcusesDiff<'a> {
+    pub(crate) fn new(cache: &'a config::Cache, options: ExcusesDiffOptions) -> Self {
+        Self { cache, options }
+    }
+
+    /// Read all items matching one of the patterns
+    fn read_items<R>(reader: R, patterns: &[Pattern]) -> Result<(DateTime<Utc>, Vec<ExcusesItem>)>
+    where
+        R: BufRead,
+    {
+        let uploaders = HashMap::new();
+        let reader = ExcusesReader::new(reader)?;
+        let generated_date = reader.generated_date();
+        let mut items = Vec::new();
+        for item in reader {
+            let item = item?;
+            if patterns.is_empty()
+                || patterns
+                    .iter()
+                    .any(|pattern| pattern.matches_item(&item, &uploaders))
+            {
+                items.push(item);
+            }
+        }
+        Ok((generated_date, items))
+    }
+}
+
+/// Changes of an item between two runs
+#[derive(Debug, Default, PartialEq, Eq)]
+struct ItemChanges {
+    /// Previous and current version
+    version: Option<(Option<PackageVersion>, Option<PackageVersion>)>,
+    /// Previous and current overall verdict
+    verdict: Option<(Verdict, Verdict)>,
+    /// Previous and current verdicts of the policies
+    policies: Vec<(String, Option<Verdict>, Option<Verdict>)>,
+    /// Previous and current age together with the age requirement
+    age: Option<(u32, u32, u32)>,
+}
+
+impl ItemChanges {
+    fn new(previous: &ExcusesItem, current: &ExcusesItem) -> Self {
+        let mut changes = Self::default();
+        if previous.new_version != current.new_version {
+            changes.version = Some((previous.new_version.clone(), current.new_version.clone()));
+        }
+        if previous.migration_policy_verdict != current.migration_policy_verdict {
+            changes.verdict = Some((
+                previous.migration_policy_verdict,
+                current.migration_policy_verdict,
+            ));
+        }
+
+        let previous_verdicts: HashMap<_, _> = previous
+            .policy_info
+            .iter()
+            .flat_map(|policy_info| policy_info.verdicts())
+            .collect();
+        let current_verdicts: HashMap<_, _> = current
+            .policy_info
+            .iter()
+            .flat_map(|policy_info| policy_info.verdicts())
+            .collect();
+        let mut policies: Vec<_> = previous_verdicts
+            .keys()
+            .chain(current_verdicts.keys())
+            .copied()
+            .collect();
+        policies.sort_unstable();
+        policies.dedup();
+        for policy in policies {
+            let previous_verdict = previous_verdicts.get(policy).copied();
+            let current_verdict = current_verdicts.get(policy).copied();
+            if previous_verdict != current_verdict {
+                changes
+                    .policies
+                    .push((policy.to_owned(), previous_verdict, current_verdict));
+            }
+        }
+
+        if let (Some(previous_age), Some(current_age)) = (
+            previous
+                .policy_info
+                .as_ref()
+                .and_then(|policy_info| policy_info.age.as_ref()),
+            current
+                .policy_info
+                .as_ref()
+                .and_then(|policy_info| policy_info.age.as_ref()),
+        ) && previous_age.current_age != current_age.current_age
+        {
+            changes.age = Some((
+                previous_age.current_age,
+                current_age.current_age,
+                current_age.age_requirement,
+            ));
+        }
+
+        changes
+    }
+
+    fn is_empty(&self) -> bool {
+        self.version.is_none()
+            && self.verdict.is_none()
+            && self.policies.is_empty()
+            && self.age.is_none()
+    }
+}
+
+/// Changes of the excuses between two runs
+#[derive(Debug, Default)]
+struct ExcusesChanges<'a> {
+    /// Items that are no longer listed in the excuses and whose version reached testing
+    migrated: Vec<&'a ExcusesItem>,
+    /// Items that are no longer listed in the excuses for other reasons, e.g., they were removed
+    /// from unstable or superseded by another upload
+    no_longer_listed: Vec<&'a ExcusesItem>,
+    /// Items that were not listed in the previous excuses
+    new: Vec<&'a ExcusesItem>,
+    /// Items listed in both excuses
+    changed: Vec<(&'a ExcusesItem, ItemChanges)>,
+}
+
+impl<'a> ExcusesChanges<'a> {
+    /// Compare the items of two runs
+    ///
+    /// Items that are no longer listed are only considered migrated if `testing_version` reports
+    /// the item's version for the source package, or no version for removals.
+    fn new<F>(previous: &'a [ExcusesItem], current: &'a [ExcusesItem], testing_version: F) -> Self
+    where
+        F: Fn(&PackageName) -> Option<&'a PackageVersion>,
+    {
+        let previous_items: HashMap<_, _> = previous
+            .iter()
+            .map(|item| (&item.item_name, item))
+            .collect();
+        let current_items: HashMap<_, _> =
+            current.iter().map(|item| (&item.item_name, item)).collect();
+
+        let mut changes = Self::default();
+        for item in previous {
+            if current_items.contains_key(&item.item_name) {
+                continue;
+            }
+            let testing_version = testing_version(&item.source);
+            let migrated = if item.item_name.removal {
+                testing_version.is_none()
+            } else {
+                testing_version.is_some() && testing_version == item.new_version.as_ref()
+            };
+            if migrated {
+                changes.migrated.push(item);
+            } else {
+                changes.no_longer_listed.push(item);
+            }
+        }
+        for item in current {
+            match previous_items.get(&item.item_name) {
+                None => changes.new.push(item),
+                Some(previous_item) => {
+                    let item_changes = ItemChanges::new(previous_item, item);
+                    if !item_changes.is_empty() {
+                        changes.changed.push((item, item_changes));
+                    }
+                }
+            }
+        }
+
+        changes
+            .migrated
+            .sort_unstable_by_key(|item| item.item_name.to_string());
+        changes
+            .no_longer_listed
+            .sort_unstable_by_key(|item| item.item_name.to_string());
+        changes
+            .new
+            .sort_unstable_by_key(|item| item.item_name.to_string());
+        changes
+            .changed
+            .sort_unstable_by_key(|(item, _)| item.item_name.to_string());
+        changes
+    }
+}
+
+fn format_version(version: Option<&PackageVersion>) -> String {
+    version.map_or_else(|| "-".into(), PackageVersion::to_string)
+}
+
+fn format_verdict(verdict: Option<Verdict>) -> String {
+    verdict.map_or_else(|| "-".into(), |verdict| verdict.to_string())
+}
+
+fn print_item(item: &ExcusesItem) {
+    println!(
+        "{} ({} to {}): {}",
+        item.item_name,
+        format_version(item.old_version.as_ref()),
+        format_version(item.new_version.as_ref()),
+        item.migration_policy_verdict
+    );
+}
+
+fn print_dropped_item(item: &ExcusesItem) {
+    println!(
+        "{} ({} to {})",
+        item.item_name,
+        format_version(item.old_version.as_ref()),
+        format_version(item.new_version.as_ref()),
+    );
+}
+
+fn print_changes(changes: &ExcusesChanges<'_>) {
+    println!("# Migrated");
+    for item in &changes.migrated {
+        print_dropped_item(item);
+    }
+
+    println!("# No longer listed");
+    for item in &changes.no_longer_listed {
+        print_dropped_item(item);
+    }
+
+    println!("# New");
+    for item in &changes.new {
+        print_item(item);
+    }
+
+    println!("# Changed");
+    for (item, item_changes) in &changes.changed {
+        print_item(item);
+        if let Some((previous, current)) = &item_changes.version {
+            println!(
+                "  version: {} -> {}",
+                format_version(previous.as_ref()),
+                format_version(current.as_ref())
+            );
+        }
+        if let Some((previous, current)) = &item_changes.verdict {
+            println!("  verdict: {previous} -> {current}");
+        }
+        for (policy, previous, current) in &item_changes.policies {
+            println!(
+                "  {policy}: {} -> {}",
+                format_verdict(*previous),
+                format_verdict(*current)
+            );
+        }
+        if let Some((previous, current, requirement)) = item_changes.age {
+            println!("  age: {previous} -> {current} days (required: {requirement} days)");
+        }
+    }
+}
+
+impl Command for ExcusesDiff<'_> {
+    fn run(&self) -> Result<()> {
+        let patterns = self
+            .options
+            .maintainer_package
+            .iter()
+            .map(|pattern| Pattern::new(pattern, self.options.regex))
+            .collect::<Result<Vec<_>>>()?;
+
+        let previous_path = match &self.options.previous {
+            Some(path) => path.clone(),
+            None => self.cache.get_cache_path(PREVIOUS_EXCUSES)?,
+        };
+        let (previous_date, previous) = Self::read_items(
+            BufReader::new(File::open(&previous_path).with_context(|| {
+                format!(
+                    "No previous excuses available at {}",
+                    previous_path.display()
+                )
+            })?),
+            &patterns,
+        )?;
+        let (current_date, current) =
+            Self::read_items(self.cache.get_cache_bufreader("excuses.yaml")?, &patterns)?;
+        let testing = SourcePackages::for_suite(self.cache, SuiteOrCodename::TESTING)?;
+
+        println!("# Changes from {previous_date} to {current_date}");
+        print_changes(&ExcusesChanges::new(&previous, &current, |source| {
+            testing.version(source)
+        }));
+        Ok(())
+    }
+}
+
+impl Downloads for ExcusesDiff<'_> {
+    fn downloads(&self) -> Vec<CacheEntries> {
+        let mut downloads = vec![CacheEntries::Excuses];
+        downloads.extend(CacheEntries::all_sources(SuiteOrCodename::TESTING));
+        downloads
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use assorted_debian_utils::excuses;
+
+    use super::*;
+
+    const PREVIOUS_DATA: &str = r"---
+generated-date: 2026-10-01 10:42:13.123456+00:00
+sources:
+- item-name: zathura
+  source: zathura
+  maintainer: Sebastian Ramacher
+  is-candidate: false
+  new-version: 0.5.11-1
+  old-version: 0.5.10-1
+  policy_info:
+    age:
+      age-requirement: 5
+      current-age: 2
+      verdict: REJECTED_TEMPORARILY
+    piuparts:
+      verdict: REJECTED_PERMANENTLY
+  excuses: []
+  migration-policy-verdict: REJECTED_PERMANENTLY
+- item-name: girara
+  source: girara
+  maintainer: Sebastian Ramacher
+  is-candidate: true
+  new-version: 0.4.5-1
+  old-version: 0.4.4-1
+  excuses: []
+  migration-policy-verdict: PASS
+";
+
+    const CURRENT_DATA: &str = r"---
+generated-date: 2026-10-02 10:42:13.123456+00:00
+sources:
+- item-name: zathura
+  source: zathura
+  maintainer: Sebastian Ramacher
+  is-candidate: false
+  new-version: 0.5.11-1
+  old-version: 0.5.10-1
+  policy_info:
+    age:
+      age-requirement: 5
+      current-age: 3
+      verdict: REJECTED_TEMPORARILY
+    piuparts:
+      verdict: PASS
+  excuses: []
+  migration-policy-verdict: REJECTED_TEMPORARILY
+- item-name: mpv
+  source: mpv
+  maintainer: Debian Multimedia Maintainers
+  is-candidate: false
+  new-version: 0.40.0-1
+  old-version: 0.39.0-1
+  excuses: []
+  migration-policy-verdict: REJECTED_TEMPORARILY
+";
+
+    #[test]
+    fn changes() {
+        let previous = excuses::from_str(PREVIOUS_DATA).unwrap().sources;
+        let current = excuses::from_str(CURRENT_DATA).unwrap().sources;
+        let girara = PackageVersion::try_from("0.4.5-1").unwrap();
+        let changes = ExcusesChanges::new(&previous, &current, |source| {
+            (*source == "girara").then_some(&girara)
+        });
+
+        assert_eq!(changes.migrated.len(), 1);
+        assert_eq!(changes.migrated[0].source, "girara");
+        assert!(changes.no_longer_listed.is_empty());
+        assert_eq!(changes.new.len(), 1);
+        assert_eq!(changes.new[0].source, "mpv");
+        assert_eq!(changes.changed.len(), 1);
+
+        let (item, item_changes) = &changes.changed[0];
+        assert_eq!(item.source, "zathura");
+        assert!(item_changes.version.is_none());
+        assert_eq!(
+            item_changes.verdict,
+            Some((Verdict::RejectedPermanently, Verdict::RejectedTemporarily))
+        );
+        assert_eq!(
+            item_changes.policies,
+            vec![(
+                "piuparts".to_owned(),
+                Some(Verdict::RejectedPermanently),
+                Some(Verdict::Pass)
+            )]
+        );
+        assert_eq!(item_changes.age, Some((2, 3, 5)));
+    }
+
+    #[test]
+    fn no_longer_listed() {
+        let previous = excuses::from_str(PREVIOUS_DATA).unwrap().sources;
+        let current = excuses::from_str(CURRENT_DATA).unwrap().sources;
+
+        // removed from unstable before migrating
+        let changes = ExcusesChanges::new(&previous, &current, |_| None);
+        assert!(changes.migrated.is_empty());
+        assert_eq!(changes.no_longer_listed.len(), 1);
+        assert_eq!(changes.no_longer_listed[0].source, "girara");
+
+        // testing still has the old version, e.g., the item was superseded
+        let old = PackageVersion::try_from("0.4.4-1").unwrap();
+        let changes = ExcusesChanges::new(&previous, &current, |_| Some(&old));
+        assert!(changes.migrated.is_empty());
+        assert_eq!(changes.no_longer_listed.len(), 1);
+    }
+
+    #[test]
+    fn filtered() {
+        let patterns = vec![Pattern::new("Sebastian Ramacher", false).unwrap()];
+        let (_, previous) = ExcusesDiff::read_items(PREVIOUS_DATA.as_bytes(), &patterns).unwrap();
+        let (_, current) = ExcusesDiff::read_items(CURRENT_DATA.as_bytes(), &patterns).unwrap();
+        assert_eq!(previous.len(), 2);
+        assert_eq!(current.len(), 1);
+
+        let changes = ExcusesChanges::new(&previous, &current, |_| None);
+        assert_eq!(changes.no_longer_listed.len(), 1);
+        assert!(changes.new.is_empty());
+        assert_eq!(changes.changed.len(), 1);
+
+        // unchanged items are not reported
+        let changes = ExcusesChanges::new(&current, &current, |_| None);
+        assert!(changes.migrated.is_empty());
+        assert!(changes.no_longer_listed.is_empty());
+        assert!(changes.new.is_empty());
+        assert!(changes.changed.is_empty());
+    }
+}
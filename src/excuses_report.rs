@@ -0,0 +1,361 @@
+// Copyright 2026 Sebastian Ramacher
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Render excuses and autoremovals as Markdown or HTML reports
+
+use std::{fmt::Write, sync::LazyLock};
+
+use assorted_debian_utils::{
+    autoremovals::AutoRemoval,
+    excuses::{ExcusesItem, Verdict},
+};
+use chrono::{DateTime, Utc};
+use regex::Regex;
+
+use crate::{cli::ReportFormat, grep_excuses::migration_estimate, utils::by_maintainer};
+
+/// Links in the excuses generated by britney
+static LINK: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"<a\s+href="([^"]*)"\s*>(.*?)</a>"#).unwrap());
+
+fn verdict_class(verdict: Verdict) -> &'static str {
+    match verdict {
+        Verdict::Pass | Verdict::PassHinted => "pass",
+        Verdict::RejectedTemporarily
+        | Verdict::RejectedCannotDetermineIfPermanent
+        | Verdict::RejectedBlockedByAnotherItem
+        | Verdict::RejectedWaitingForAnotherItem => "pending",
+        Verdict::RejectedPermanently | Verdict::RejectedNeedsApproval => "rejected",
+    }
+}
+
+fn format_version<T>(version: Option<&T>) -> String
+where
+    T: ToString,
+{
+    version.map_or_else(|| "-".into(), ToString::to_string)
+}
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Escape characters with a special meaning in Markdown
+fn escape_markdown(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Convert the links of an excuse and strip all other tags
+///
+/// `link` receives the URL and the text of the link and `text` the text in between.
+fn convert_excuse<L, T>(line: &str, link: L, text: T) -> String
+where
+    L: Fn(&str, &str) -> String,
+    T: Fn(&str) -> String,
+{
+    let strip = |value: &str| text(&voca_rs::strip::strip_tags(value));
+    let mut output = String::new();
+    let mut last = 0;
+    for captures in LINK.captures_iter(line) {
+        let matched = captures.get(0).unwrap();
+        output.push_str(&strip(&line[last..matched.start()]));
+        output.push_str(&link(
+            &captures[1],
+            &text(&voca_rs::strip::strip_tags(&captures[2])),
+        ));
+        last = matched.end();
+    }
+    output.push_str(&strip(&line[last..]));
+    output
+}
+
+/// Convert links to Markdown, strip all other tags and escape the text
+fn excuse_to_markdown(line: &str) -> String {
+    convert_excuse(
+        line,
+        |url, text| format!("[{text}](<{url}>)"),
+        escape_markdown,
+    )
+}
+
+/// Keep links, strip all other tags and escape the text
+fn excuse_to_html(line: &str) -> String {
+    convert_excuse(
+        line,
+        |url, text| format!(r#"<a href="{}">{text}</a>"#, escape_html(url)),
+        escape_html,
+    )
+}
+
+fn markdown_verdict(verdict: Verdict) -> String {
+    let marker = match verdict {
+        Verdict::Pass | Verdict::PassHinted => "🟢",
+        Verdict::RejectedTemporarily
+        | Verdict::RejectedCannotDetermineIfPermanent
+        | Verdict::RejectedBlockedByAnotherItem
+        | Verdict::RejectedWaitingForAnotherItem => "🟠",
+        Verdict::RejectedPermanently | Verdict::RejectedNeedsApproval => "🔴",
+    };
+    format!("{marker} `{verdict}`")
+}
+
+fn html_verdict(verdict: Verdict) -> String {
+    format!(
+        r#"<span class="{}">{verdict}</span>"#,
+        verdict_class(verdict)
+    )
+}
+
+fn render_markdown(
+    items: &[&ExcusesItem],
+    autoremovals: &[&AutoRemoval],
+    generated_date: DateTime<Utc>,
+) -> Result<String, std::fmt::Error> {
+    let mut output = String::new();
+    writeln!(output, "# Excuses report")?;
+    writeln!(output)?;
+    writeln!(output, "Excuses generated on {generated_date}.")?;
+
+    for (maintainer, items) in by_maintainer(items, "Unknown maintainer", |item| *item) {
+        writeln!(output)?;
+        writeln!(output, "## {}", escape_markdown(maintainer))?;
+        for item in items {
+            writeln!(output)?;
+            writeln!(
+                output,
+                "### {} ({} to {}): {}",
+                escape_markdown(&item.item_name.to_string()),
+                escape_markdown(&format_version(item.old_version.as_ref())),
+                escape_markdown(&format_version(item.new_version.as_ref())),
+                markdown_verdict(item.migration_policy_verdict)
+            )?;
+            writeln!(output)?;
+            writeln!(
+                output,
+                "* Earliest migration: {}",
+                escape_markdown(&migration_estimate(item, generated_date).to_string())
+            )?;
+            for line in &item.excuses {
+                writeln!(output, "* {}", excuse_to_markdown(line))?;
+            }
+            if let Some(policy_info) = &item.policy_info {
+                for (policy, verdict) in policy_info.verdicts() {
+                    writeln!(output, "* {policy}: {}", markdown_verdict(verdict))?;
+                }
+            }
+        }
+    }
+
+    if !autoremovals.is_empty() {
+        writeln!(output)?;
+        writeln!(output, "## Autoremovals")?;
+        writeln!(output)?;
+        for autoremoval in autoremovals {
+            writeln!(
+                output,
+                "* {} {}: removal on {}, bugs: {}",
+                escape_markdown(&autoremoval.source),
+                escape_markdown(&autoremoval.version.to_string()),
+                autoremoval.removal_date.format("%Y-%m-%d"),
+                autoremoval
+                    .bugs
+                    .iter()
+                    .map(|bug| format!("[#{bug}](https://bugs.debian.org/{bug})"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )?;
+        }
+    }
+
+    Ok(output)
+}
+
+fn render_html(
+    items: &[&ExcusesItem],
+    autoremovals: &[&AutoRemoval],
+    generated_date: DateTime<Utc>,
+) -> Result<String, std::fmt::Error> {
+    let mut output = String::new();
+    writeln!(output, "<!DOCTYPE html>")?;
+    writeln!(output, r#"<html lang="en">"#)?;
+    writeln!(output, "<head>")?;
+    writeln!(output, r#"<meta charset="utf-8">"#)?;
+    writeln!(output, "<title>Excuses report</title>")?;
+    writeln!(
+        output,
+        "<style>.pass {{ color: green; }} .pending {{ color: darkorange; }} .rejected {{ color: red; }}</style>"
+    )?;
+    writeln!(output, "</head>")?;
+    writeln!(output, "<body>")?;
+    writeln!(output, "<h1>Excuses report</h1>")?;
+    writeln!(output, "<p>Excuses generated on {generated_date}.</p>")?;
+
+    for (maintainer, items) in by_maintainer(items, "Unknown maintainer", |item| *item) {
+        writeln!(output, "<h2>{}</h2>", escape_html(maintainer))?;
+        for item in items {
+            writeln!(
+                output,
+                "<h3>{} ({} to {}): {}</h3>",
+                escape_html(&item.item_name.to_string()),
+                escape_html(&format_version(item.old_version.as_ref())),
+                escape_html(&format_version(item.new_version.as_ref())),
+                html_verdict(item.migration_policy_verdict)
+            )?;
+            writeln!(output, "<ul>")?;
+            writeln!(
+                output,
+                "<li>Earliest migration: {}</li>",
+                escape_html(&migration_estimate(item, generated_date).to_string())
+            )?;
+            for line in &item.excuses {
+                writeln!(output, "<li>{}</li>", excuse_to_html(line))?;
+            }
+            if let Some(policy_info) = &item.policy_info {
+                for (policy, verdict) in policy_info.verdicts() {
+                    writeln!(
+                        output,
+                        "<li>{}: {}</li>",
+                        escape_html(policy),
+                        html_verdict(verdict)
+                    )?;
+                }
+            }
+            writeln!(output, "</ul>")?;
+        }
+    }
+
+    if !autoremovals.is_empty() {
+        writeln!(output, "<h2>Autoremovals</h2>")?;
+        writeln!(output, "<ul>")?;
+        for autoremoval in autoremovals {
+            writeln!(
+                output,
+                "<li>{} {}: removal on {}, bugs: {}</li>",
+                escape_html(&autoremoval.source),
+                escape_html(&autoremoval.version.to_string()),
+                autoremoval.removal_date.format("%Y-%m-%d"),
+                autoremoval
+                    .bugs
+                    .iter()
+                    .map(|bug| {
+                        let bug = escape_html(bug);
+                        format!(r#"<a href="https://bugs.debian.org/{bug}">#{bug}</a>"#)
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )?;
+        }
+        writeln!(output, "</ul>")?;
+    }
+
+    writeln!(output, "</body>")?;
+    writeln!(output, "</html>")?;
+    Ok(output)
+}
+
+/// Render excuses and autoremovals in the given format
+///
+/// Returns `None` for plain text, which is printed directly by the commands.
+pub(crate) fn render(
+    format: ReportFormat,
+    items: &[&ExcusesItem],
+    autoremovals: &[&AutoRemoval],
+    generated_date: DateTime<Utc>,
+) -> Result<Option<String>, std::fmt::Error> {
+    match format {
+        ReportFormat::Text => Ok(None),
+        ReportFormat::Markdown => render_markdown(items, autoremovals, generated_date).map(Some),
+        ReportFormat::Html => render_html(items, autoremovals, generated_date).map(Some),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use assorted_debian_utils::excuses;
+
+    use super::*;
+
+    const TEST_DATA: &str = r#"---
+generated-date: 2026-10-01 10:42:13.123456+00:00
+sources:
+- item-name: zathura
+  source: zathura
+  maintainer: Sebastian Ramacher
+  is-candidate: false
+  new-version: 0.5.11-1
+  old-version: 0.5.10-1
+  policy_info:
+    age:
+      age-requirement: 5
+      current-age: 2
+      verdict: REJECTED_TEMPORARILY
+  excuses:
+  - 'Migration status for zathura (0.5.10-1 to 0.5.11-1): <a href="https://example.org/zathura">BLOCKED</a> & waiting for _tpu <b>now</b>'
+  migration-policy-verdict: REJECTED_TEMPORARILY
+- item-name: mpv
+  source: mpv
+  maintainer: Debian Multimedia Maintainers <debian-multimedia@lists.debian.org>
+  is-candidate: true
+  new-version: 0.40.0-1
+  old-version: 0.39.0-1
+  excuses: []
+  migration-policy-verdict: PASS
+"#;
+
+    #[test]
+    fn markdown() {
+        let excuses = excuses::from_str(TEST_DATA).unwrap();
+        let items: Vec<_> = excuses.sources.iter().collect();
+        let report = render(ReportFormat::Markdown, &items, &[], excuses.generated_date)
+            .unwrap()
+            .unwrap();
+
+        assert!(report.contains("[BLOCKED](<https://example.org/zathura>) & waiting for \\_tpu"));
+        assert!(report.contains("* Earliest migration: "));
+        assert!(report.contains("### zathura (0.5.10-1 to 0.5.11-1): 🟠 `REJECTED_TEMPORARILY`"));
+        assert!(report.contains("* age: 🟠 `REJECTED_TEMPORARILY`"));
+        // grouped by maintainer
+        let multimedia = report.find("## Debian Multimedia Maintainers").unwrap();
+        let sebastian = report.find("## Sebastian Ramacher").unwrap();
+        assert!(multimedia < report.find("### mpv").unwrap());
+        assert!(sebastian < report.find("### zathura").unwrap());
+        assert!(!report.contains("Autoremovals"));
+    }
+
+    #[test]
+    fn html() {
+        let excuses = excuses::from_str(TEST_DATA).unwrap();
+        let items: Vec<_> = excuses.sources.iter().collect();
+        let report = render(ReportFormat::Html, &items, &[], excuses.generated_date)
+            .unwrap()
+            .unwrap();
+
+        assert!(report.starts_with("<!DOCTYPE html>"));
+        assert!(report.contains(
+            r#"<a href="https://example.org/zathura">BLOCKED</a> &amp; waiting for _tpu now</li>"#
+        ));
+        assert!(report.contains("<li>Earliest migration: "));
+        assert!(report.contains(
+            "<h2>Debian Multimedia Maintainers &lt;debian-multimedia@lists.debian.org&gt;</h2>"
+        ));
+        assert!(report.contains(r#"<span class="pass">PASS</span>"#));
+        assert!(report.trim_end().ends_with("</html>"));
+
+        assert!(
+            render(ReportFormat::Text, &items, &[], excuses.generated_date)
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,400 @@
+// Copyright 2026 Sebastian Ramacher
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Plan give-backs for missing builds listed in the excuses
+
+use std::{
+    collections::{HashMap, VecDeque},
+    fs::File,
+    io::{BufRead, BufReader},
+    path::Path,
+};
+
+use anyhow::{Context, Result, anyhow};
+use assorted_debian_utils::{
+    architectures::Architecture,
+    excuses::ExcusesItem,
+    package::{PackageName, PackageRelationship},
+    version::PackageVersion,
+    wb::{BuildState, DepWait, GiveBack, Info, SourceSpecifier, WBCommand, WBCommandBuilder},
+};
+use log::warn;
+
+use crate::{cli::BaseOptions, utils::query_wb_commands};
+
+/// A build of a source package on an architecture
+pub(crate) type Build = (PackageName, PackageVersion, Architecture);
+
+/// States of builds as reported by wanna-build
+pub(crate) type BuildStates = HashMap<Build, BuildState>;
+
+/// Missing builds of an item
+pub(crate) fn missing_builds(item: &ExcusesItem) -> Vec<Build> {
+    let (Some(missing_builds), Some(version)) = (&item.missing_builds, &item.new_version) else {
+        return Vec::new();
+    };
+    missing_builds
+        .on_architectures
+        .iter()
+        .map(|architecture| (item.source.clone(), version.clone(), *architecture))
+        .collect()
+}
+
+/// Read build states from a dump
+///
+/// Each line consists of `$source_$version $architecture $state`. Empty lines and lines starting
+/// with `#` are ignored. Lines with unknown states are skipped with a warning.
+fn read_build_states<R>(reader: R) -> Result<BuildStates>
+where
+    R: BufRead,
+{
+    let mut states = BuildStates::new();
+    for line in reader.lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let parse = || -> Result<_> {
+            let [package, architecture, state] = line
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .try_into()
+                .map_err(|_| anyhow!("expected three fields"))?;
+            let (source, version) = package
+                .split_once('_')
+                .ok_or_else(|| anyhow!("expected $source_$version"))?;
+            Ok((
+                (
+                    PackageName::try_from(source)?,
+                    PackageVersion::try_from(version)?,
+                    Architecture::try_from(architecture)?,
+                ),
+                state,
+            ))
+        };
+        let (build, state) = parse().with_context(|| format!("Invalid build state '{line}'"))?;
+        match BuildState::try_from(state) {
+            Ok(state) => {
+                states.insert(build, state);
+            }
+            Err(err) => warn!("Skipping '{line}': {err}"),
+        }
+    }
+    Ok(states)
+}
+
+/// Extract the state from the output of `wb info`
+fn parse_info_state(output: &str) -> Option<BuildState> {
+    output.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        if key.trim() == "State" {
+            BuildState::try_from(value.trim()).ok()
+        } else {
+            None
+        }
+    })
+}
+
+/// Split the output of multiple `wb info` commands into the output per package
+///
+/// The output for each package starts with an unindented line `$source:`.
+fn split_info_output(output: &str) -> Vec<(&str, String)> {
+    let mut sections: Vec<(&str, String)> = Vec::new();
+    for line in output.lines() {
+        if line.starts_with(char::is_whitespace) {
+            if let Some((_, section)) = sections.last_mut() {
+                section.push_str(line);
+                section.push('\n');
+            }
+        } else if let Some((source, _)) = line.split_once(':') {
+            sections.push((source.trim(), format!("{line}\n")));
+        }
+    }
+    sections
+}
+
+/// Load the build states from a dump or query them from wanna-build
+pub(crate) async fn load_build_states(
+    builds: &[Build],
+    dump: Option<&Path>,
+    base_options: &BaseOptions,
+) -> Result<BuildStates> {
+    if let Some(path) = dump {
+        return read_build_states(BufReader::new(
+            File::open(path).with_context(|| format!("Failed to open {}", path.display()))?,
+        ))
+        .with_context(|| format!("Failed to read build states from {}", path.display()));
+    }
+    if builds.is_empty() {
+        return Ok(BuildStates::new());
+    }
+
+    let commands = builds
+        .iter()
+        .map(|(source, version, architecture)| {
+            let mut source_specifier = SourceSpecifier::new(source);
+            source_specifier
+                .with_version(version)
+                .with_archive_architectures(&[*architecture]);
+            Ok(Info::new(&source_specifier)?.build())
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let output = query_wb_commands(commands, base_options).await?;
+    Ok(match_info_output(builds, &output))
+}
+
+/// Match the sections of the output of `wb info` to the builds by source package
+///
+/// Sections of the same source package are assigned in the order of the builds. Builds without a
+/// section and sections without a build are reported.
+fn match_info_output(builds: &[Build], output: &str) -> BuildStates {
+    let mut sections: HashMap<&str, VecDeque<String>> = HashMap::new();
+    for (source, section) in split_info_output(output) {
+        sections.entry(source).or_default().push_back(section);
+    }
+
+    let mut states = BuildStates::new();
+    for build in builds {
+        let Some(section) = sections
+            .get_mut(build.0.as_ref())
+            .and_then(VecDeque::pop_front)
+        else {
+            warn!(
+                "{}_{} on {}: wb did not report a build state",
+                build.0, build.1, build.2
+            );
+            continue;
+        };
+        if let Some(state) = parse_info_state(&section) {
+            states.insert(build.clone(), state);
+        } else {
+            warn!(
+                "{}_{} on {}: unable to determine build state",
+                build.0, build.1, build.2
+            );
+        }
+    }
+    for (source, leftovers) in sections {
+        if !leftovers.is_empty() {
+            warn!(
+                "wb reported {} build state(s) of {source} without a matching build",
+                leftovers.len()
+            );
+        }
+    }
+    states
+}
+
+/// Action planned for a missing build
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum GiveBackAction {
+    /// Give back the build
+    GiveBack(WBCommand),
+    /// Suggest a dep-wait for a build with uninstallable build dependencies
+    ///
+    /// The command is only available if the unsatisfiable build dependencies are known.
+    DepWait(Option<WBCommand>),
+}
+
+/// Plan the action for a missing build of an item
+pub(crate) fn plan_give_back(
+    item: &ExcusesItem,
+    build: &Build,
+    state: BuildState,
+) -> Result<Option<GiveBackAction>> {
+    let (source, version, architecture) = build;
+    let mut source_specifier = SourceSpecifier::new(source);
+    source_specifier
+        .with_version(version)
+        .with_archive_architectures(&[*architecture]);
+
+    match state {
+        BuildState::BuildAttempted | BuildState::MaybeFailed => Ok(Some(GiveBackAction::GiveBack(
+            GiveBack::new(&source_specifier)?.build(),
+        ))),
+        BuildState::BDUninstallable => {
+            let dependencies: Vec<_> = item
+                .policy_info
+                .as_ref()
+                .and_then(|policy_info| policy_info.build_depends.as_ref())
+                .and_then(|build_depends| {
+                    build_depends
+                        .unsatisfiable_arch_build_depends
+                        .get(architecture)
+                })
+                .into_iter()
+                .flatten()
+                .filter_map(|dependency| PackageRelationship::try_from(dependency.as_str()).ok())
+                .collect();
+            if dependencies.is_empty() {
+                Ok(Some(GiveBackAction::DepWait(None)))
+            } else {
+                Ok(Some(GiveBackAction::DepWait(Some(
+                    DepWait::new(&source_specifier, &dependencies)?.build(),
+                ))))
+            }
+        }
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use assorted_debian_utils::excuses;
+
+    use super::*;
+
+    const TEST_DATA: &str = r"---
+generated-date: 2026-10-01 10:42:13.123456+00:00
+sources:
+- item-name: zathura
+  source: zathura
+  maintainer: Sebastian Ramacher
+  is-candidate: false
+  new-version: 0.5.11-1
+  old-version: 0.5.10-1
+  missing-builds:
+    on-architectures:
+    - amd64
+    - i386
+    - s390x
+  policy_info:
+    build-depends:
+      unsatisfiable-arch-build-depends:
+        i386:
+        - libgirara-dev (>= 0.4.5)
+      verdict: REJECTED_PERMANENTLY
+  excuses: []
+  migration-policy-verdict: REJECTED_PERMANENTLY
+";
+
+    #[test]
+    fn states() {
+        let states = read_build_states(
+            "# dump\nzathura_0.5.11-1 amd64 Build-Attempted\n\nzathura_0.5.11-1 i386 BD-Uninstallable\n"
+                .as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(states.len(), 2);
+        assert_eq!(
+            states[&(
+                PackageName::try_from("zathura").unwrap(),
+                PackageVersion::try_from("0.5.11-1").unwrap(),
+                Architecture::Amd64
+            )],
+            BuildState::BuildAttempted
+        );
+        assert!(read_build_states("zathura amd64 Build-Attempted\n".as_bytes()).is_err());
+        // unknown states are skipped
+        let states = read_build_states(
+            "zathura_0.5.11-1 amd64 Maybe-Broken\nzathura_0.5.11-1 i386 Dep-Wait-Removed\n"
+                .as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(states.len(), 1);
+        assert_eq!(
+            states[&(
+                PackageName::try_from("zathura").unwrap(),
+                PackageVersion::try_from("0.5.11-1").unwrap(),
+                Architecture::I386
+            )],
+            BuildState::DepWaitRemoved
+        );
+        assert!(read_build_states("zathura_0.5.11-1 amd64\n".as_bytes()).is_err());
+
+        assert_eq!(
+            parse_info_state(
+                "zathura:\n  Package             : zathura\n  State               : Maybe-Failed\n"
+            ),
+            Some(BuildState::MaybeFailed)
+        );
+        assert_eq!(parse_info_state("zathura: not registered\n"), None);
+
+        let outputs = split_info_output(
+            "zathura:\n  Package             : zathura\n  State               : Maybe-Failed\n\
+             girara: not registered\n\
+             zathura:\n  Package             : zathura\n  State               : Build-Attempted\n",
+        );
+        assert_eq!(
+            outputs
+                .iter()
+                .map(|(source, output)| (*source, parse_info_state(output)))
+                .collect::<Vec<_>>(),
+            vec![
+                ("zathura", Some(BuildState::MaybeFailed)),
+                ("girara", None),
+                ("zathura", Some(BuildState::BuildAttempted)),
+            ]
+        );
+    }
+
+    #[test]
+    fn match_sections() {
+        let build = |source: &str, architecture| {
+            (
+                PackageName::try_from(source).unwrap(),
+                PackageVersion::try_from("1-1").unwrap(),
+                architecture,
+            )
+        };
+        let builds = [
+            build("zathura", Architecture::Amd64),
+            build("girara", Architecture::Amd64),
+            build("zathura", Architecture::I386),
+            build("poppler", Architecture::Amd64),
+        ];
+        // sections are out of order, poppler is missing and an unrelated section is included
+        let states = match_info_output(
+            &builds,
+            "girara:\n  State               : Built\n\
+             zathura:\n  State               : Maybe-Failed\n\
+             mupdf:\n  State               : Installed\n\
+             zathura:\n  State               : Build-Attempted\n",
+        );
+        assert_eq!(states.len(), 3);
+        assert_eq!(states[&builds[0]], BuildState::MaybeFailed);
+        assert_eq!(states[&builds[1]], BuildState::Built);
+        assert_eq!(states[&builds[2]], BuildState::BuildAttempted);
+        assert!(!states.contains_key(&builds[3]));
+    }
+
+    #[test]
+    fn plan() {
+        let excuses = excuses::from_str(TEST_DATA).unwrap();
+        let item = &excuses.sources[0];
+        let builds = missing_builds(item);
+        assert_eq!(builds.len(), 3);
+
+        assert_eq!(
+            plan_give_back(item, &builds[0], BuildState::BuildAttempted)
+                .unwrap()
+                .map(|action| match action {
+                    GiveBackAction::GiveBack(command) => command.to_string(),
+                    GiveBackAction::DepWait(_) => String::new(),
+                }),
+            Some("gb zathura_0.5.11-1 . amd64 . unstable".to_owned())
+        );
+        assert_eq!(
+            plan_give_back(item, &builds[1], BuildState::BDUninstallable)
+                .unwrap()
+                .map(|action| match action {
+                    GiveBackAction::DepWait(command) => command.map(|command| command.to_string()),
+                    GiveBackAction::GiveBack(_) => None,
+                }),
+            Some(Some(
+                "dw zathura_0.5.11-1 . i386 . unstable . -m \"libgirara-dev (>= 0.4.5)\""
+                    .to_owned()
+            ))
+        );
+        assert_eq!(
+            plan_give_back(item, &builds[2], BuildState::BDUninstallable).unwrap(),
+            Some(GiveBackAction::DepWait(None))
+        );
+        assert_eq!(
+            plan_give_back(item, &builds[2], BuildState::Building).unwrap(),
+            None
+        );
+    }
+}
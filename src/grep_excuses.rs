@@ -1,21 +1,35 @@
 // Copyright 2022-2024 Sebastian Ramacher
 // SPDX-License-Identifier: LGPL-3.0-or-later
 
-use std::fmt::Display;
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    fmt::{self, Display},
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use assorted_debian_utils::{
+    architectures::Architecture,
+    archive::SuiteOrCodename,
     autoremovals::{self, AutoRemoval},
-    excuses::{self, ExcusesItem},
+    excuses::{
+        AutopkgtestResult, AutopkgtestStatus, Excuses, ExcusesIndex, ExcusesItem, ExcusesReader,
+        Verdict,
+    },
+    maintainer::Maintainer,
     package::PackageName,
     version::PackageVersion,
 };
-use chrono::Utc;
+use chrono::{DateTime, TimeDelta, Utc};
+use regex::{Regex, RegexBuilder};
 
 use crate::{
     Command, Downloads,
-    cli::GrepExcusesOptions,
-    config::{self, CacheEntries},
+    cli::{GrepExcusesOptions, OutputFormat, ReportFormat},
+    config::{self, CacheEntries, CachePaths},
+    excuses_report, source_packages,
 };
 
 pub(crate) struct GrepExcuses<'a> {
@@ -29,6 +43,111 @@ impl<'a> GrepExcuses<'a> {
     }
 }
 
+/// Pattern to match packages and maintainers
+#[derive(Debug)]
+pub(crate) enum Pattern {
+    /// Match the complete string ignoring case
+    Exact(String),
+    /// Match a regular expression or a glob pattern converted to a regular expression
+    Regex(Regex),
+}
+
+/// Convert a glob pattern to an anchored regular expression
+fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::from("^");
+    let mut in_class = false;
+    for c in glob.chars() {
+        match c {
+            '*' if !in_class => regex.push_str(".*"),
+            '?' if !in_class => regex.push('.'),
+            '[' if !in_class => {
+                in_class = true;
+                regex.push('[');
+            }
+            '!' if in_class && regex.ends_with('[') => regex.push('^'),
+            ']' if in_class => {
+                in_class = false;
+                regex.push(']');
+            }
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+    regex
+}
+
+impl Pattern {
+    pub(crate) fn new(pattern: &str, is_regex: bool) -> Result<Self> {
+        let regex = if is_regex {
+            pattern.to_string()
+        } else if pattern.contains(['*', '?', '[']) {
+            glob_to_regex(pattern)
+        } else {
+            return Ok(Self::Exact(pattern.to_string()));
+        };
+
+        Ok(Self::Regex(
+            RegexBuilder::new(&regex)
+                .case_insensitive(true)
+                .build()
+                .with_context(|| format!("Invalid pattern '{pattern}'"))?,
+        ))
+    }
+
+    fn matches(&self, value: &str) -> bool {
+        match self {
+            Self::Exact(pattern) => pattern.eq_ignore_ascii_case(value),
+            Self::Regex(regex) => regex.is_match(value),
+        }
+    }
+
+    fn matches_maintainer(&self, maintainer: &Maintainer) -> bool {
+        self.matches(&maintainer.name)
+            || maintainer
+                .email
+                .as_ref()
+                .is_some_and(|email| self.matches(email))
+            || self.matches(&maintainer.to_string())
+    }
+
+    pub(crate) fn matches_item(
+        &self,
+        excuse: &ExcusesItem,
+        uploaders: &HashMap<PackageName, Vec<Maintainer>>,
+    ) -> bool {
+        self.matches(excuse.source.as_ref())
+            || excuse
+                .maintainer
+                .as_deref()
+                .is_some_and(|maintainer| self.matches_maintainer(&Maintainer::from(maintainer)))
+            || uploaders.get(&excuse.source).is_some_and(|uploaders| {
+                uploaders
+                    .iter()
+                    .any(|uploader| self.matches_maintainer(uploader))
+            })
+    }
+
+    /// Look up the matching items in the index
+    ///
+    /// Exact patterns are looked up by source and maintainer unless uploaders need to be matched.
+    fn lookup<'a>(
+        &self,
+        index: &'a ExcusesIndex,
+        uploaders: &HashMap<PackageName, Vec<Maintainer>>,
+    ) -> Vec<&'a ExcusesItem> {
+        match self {
+            Self::Exact(name) if uploaders.is_empty() => {
+                index.by_source_or_maintainer(name).collect()
+            }
+            _ => index
+                .items()
+                .iter()
+                .filter(|item| self.matches_item(item, uploaders))
+                .collect(),
+        }
+    }
+}
+
 fn print_excuse(excuse: &ExcusesItem) {
     println!(
         "{} ({} to {})",
@@ -50,6 +169,183 @@ fn print_excuse(excuse: &ExcusesItem) {
     }
 }
 
+fn print_autopkgtest_results(results: &HashMap<Architecture, AutopkgtestResult>, indent: &str) {
+    let mut results: Vec<_> = results.iter().collect();
+    results.sort_unstable_by(|(lhs, _), (rhs, _)| lhs.as_ref().cmp(rhs.as_ref()));
+    for (architecture, result) in results {
+        match &result.log_url {
+            Some(log_url) => println!("{indent}{architecture}: {} {log_url}", result.status),
+            None => println!("{indent}{architecture}: {}", result.status),
+        }
+    }
+}
+
+fn print_autopkgtests(excuse: &ExcusesItem) {
+    let Some(autopkgtest) = excuse
+        .policy_info
+        .as_ref()
+        .and_then(|policy_info| policy_info.autopkgtest.as_ref())
+    else {
+        return;
+    };
+
+    let mut tests: Vec<_> = autopkgtest.results.iter().collect();
+    tests.sort_unstable_by_key(|(test, _)| test.as_str());
+    for (test, results) in tests {
+        println!("  autopkgtest for {test}:");
+        print_autopkgtest_results(results, "    ");
+    }
+}
+
+/// Get the results of the package's tests that regressed with the item
+fn regressions<'a>(
+    excuse: &'a ExcusesItem,
+    package: &'a str,
+) -> Vec<(&'a str, HashMap<Architecture, AutopkgtestResult>)> {
+    let Some(autopkgtest) = excuse
+        .policy_info
+        .as_ref()
+        .and_then(|policy_info| policy_info.autopkgtest.as_ref())
+    else {
+        return Vec::new();
+    };
+
+    autopkgtest
+        .results_for_package(package)
+        .filter_map(|(test, results)| {
+            let results: HashMap<_, _> = results
+                .iter()
+                .filter(|(_, result)| result.status == AutopkgtestStatus::Regression)
+                .map(|(architecture, result)| (*architecture, result.clone()))
+                .collect();
+            (!results.is_empty()).then_some((test, results))
+        })
+        .collect()
+}
+
+/// Interval between two runs of britney
+const BRITNEY_RUN_INTERVAL: TimeDelta = TimeDelta::hours(6);
+
+/// Estimate of the earliest migration of an item
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum MigrationEstimate {
+    /// The item can migrate in the run at the given date once the pending policies pass
+    Date(DateTime<Utc>, Vec<String>),
+    /// The item waits for other items to migrate
+    WaitingForOtherItems,
+    /// The item cannot migrate without intervention
+    Blocked(Vec<String>),
+}
+
+/// Estimate the earliest migration of an item from excuses generated at the given date
+///
+/// britney is assumed to run in fixed intervals starting from the date of the excuses. The age
+/// requirement is met after the remaining number of days. Policies that are rejected temporarily
+/// or where britney cannot determine if the issue is permanent, e.g., running autopkgtests or
+/// missing builds, are considered to be pending.
+pub(crate) fn migration_estimate(
+    excuse: &ExcusesItem,
+    generated_date: DateTime<Utc>,
+) -> MigrationEstimate {
+    let mut remaining_age = 0;
+    let mut pending = Vec::new();
+    let mut blocked = Vec::new();
+    let mut waiting = false;
+    if let Some(policy_info) = &excuse.policy_info {
+        for (name, verdict) in policy_info.verdicts() {
+            match verdict {
+                Verdict::Pass | Verdict::PassHinted => {}
+                Verdict::RejectedTemporarily if name == "age" => {
+                    if let Some(age) = &policy_info.age {
+                        remaining_age = age.age_requirement.saturating_sub(age.current_age);
+                    }
+                }
+                Verdict::RejectedTemporarily | Verdict::RejectedCannotDetermineIfPermanent => {
+                    pending.push(name.to_owned());
+                }
+                Verdict::RejectedPermanently | Verdict::RejectedNeedsApproval => {
+                    blocked.push(format!("{name}: {verdict}"));
+                }
+                Verdict::RejectedBlockedByAnotherItem | Verdict::RejectedWaitingForAnotherItem => {
+                    waiting = true;
+                }
+            }
+        }
+    }
+
+    match excuse.migration_policy_verdict {
+        Verdict::RejectedPermanently | Verdict::RejectedNeedsApproval if blocked.is_empty() => {
+            // no policy explains the verdict
+            blocked.push(excuse.migration_policy_verdict.to_string());
+        }
+        Verdict::RejectedBlockedByAnotherItem | Verdict::RejectedWaitingForAnotherItem => {
+            waiting = true;
+        }
+        _ => {}
+    }
+
+    if !blocked.is_empty() {
+        MigrationEstimate::Blocked(blocked)
+    } else if waiting || excuse.invalidated_by_other_package == Some(true) {
+        MigrationEstimate::WaitingForOtherItems
+    } else {
+        // the earliest run is the next one after the excuses were generated
+        let date = generated_date + BRITNEY_RUN_INTERVAL.max(TimeDelta::days(remaining_age.into()));
+        MigrationEstimate::Date(date, pending)
+    }
+}
+
+impl Display for MigrationEstimate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Date(date, pending) if pending.is_empty() => {
+                write!(f, "{}", date.format("%Y-%m-%d %H:%M UTC"))
+            }
+            Self::Date(date, pending) => write!(
+                f,
+                "{} (pending: {})",
+                date.format("%Y-%m-%d %H:%M UTC"),
+                pending.join(", ")
+            ),
+            Self::WaitingForOtherItems => write!(f, "unknown, waiting for other items to migrate"),
+            Self::Blocked(reasons) => {
+                write!(f, "requires intervention ({})", reasons.join(", "))
+            }
+        }
+    }
+}
+
+fn print_migration_estimate(excuse: &ExcusesItem, generated_date: DateTime<Utc>) {
+    println!(
+        "  Earliest migration: {}",
+        migration_estimate(excuse, generated_date)
+    );
+}
+
+fn print_held_back<'a>(package: &str, excuses: impl Iterator<Item = &'a ExcusesItem>) {
+    for excuse in excuses {
+        // the package's own tests are already shown with its excuses
+        if excuse.source == package {
+            continue;
+        }
+        for (test, results) in regressions(excuse, package) {
+            println!(
+                "{} ({} to {}): held back by regression in autopkgtest for {test}",
+                excuse.source,
+                excuse
+                    .old_version
+                    .as_ref()
+                    .map_or_else(|| "-".into(), PackageVersion::to_string),
+                excuse
+                    .new_version
+                    .as_ref()
+                    .map_or_else(|| "-".into(), PackageVersion::to_string)
+            );
+            print_autopkgtest_results(&results, "  ");
+        }
+    }
+}
+
 fn print_autoremoval(autoremoval: &AutoRemoval) {
     fn print_indented<T>(items: &[T])
     where
@@ -89,35 +385,136 @@ fn print_autoremoval(autoremoval: &AutoRemoval) {
     );
 }
 
+fn export_excuses(excuses: &Excuses, path: &Path, format: OutputFormat) -> Result<()> {
+    let mut writer = BufWriter::new(
+        File::create(path).with_context(|| format!("Failed to create {}", path.display()))?,
+    );
+    match format {
+        OutputFormat::Yaml => serde_yaml::to_writer(&mut writer, excuses)?,
+        OutputFormat::Json => serde_json::to_writer_pretty(&mut writer, excuses)?,
+    }
+    writer.flush()?;
+    Ok(())
+}
+
 impl Command for GrepExcuses<'_> {
     fn run(&self) -> Result<()> {
-        // parse excuses
-        let excuses = excuses::from_reader(self.cache.get_cache_bufreader("excuses.yaml")?)?;
+        // parse excuses, but only keep the relevant items
+        let reader = ExcusesReader::new(self.cache.get_cache_bufreader("excuses.yaml")?)?;
+        let generated_date = reader.generated_date();
+        let wanted: HashSet<_> = self
+            .options
+            .maintainer_package
+            .iter()
+            .map(String::as_str)
+            .collect();
+        let patterns = self
+            .options
+            .maintainer_package
+            .iter()
+            .map(|pattern| Pattern::new(pattern, self.options.regex))
+            .collect::<Result<Vec<_>>>()?;
+        let uploaders = if self.options.uploaders {
+            source_packages::uploaders(&[self.cache.get_source_path(SuiteOrCodename::UNSTABLE)?])?
+        } else {
+            HashMap::new()
+        };
+        // only the matching items are kept
+        let mut items = ExcusesIndex::default();
+        // items held back by autopkgtest regressions of the queried packages
+        let mut held_back = Vec::new();
+        for item in reader {
+            let item = item?;
+            if self.options.only_failing && item.migration_policy_verdict == Verdict::Pass {
+                continue;
+            }
+            if patterns
+                .iter()
+                .any(|pattern| pattern.matches_item(&item, &uploaders))
+            {
+                items.insert(item);
+            } else if self.options.autopkgtests
+                && wanted
+                    .iter()
+                    .any(|package| !regressions(&item, package).is_empty())
+            {
+                held_back.push(item);
+            }
+        }
         // parse autoremovals
         let autoremovals =
             autoremovals::from_reader(self.cache.get_cache_bufreader("autoremovals.yaml")?)?;
 
-        for maintainer_package in &self.options.maintainer_package {
-            if let Ok(package) = PackageName::try_from(maintainer_package.as_str()) {
-                // first print the autoremoval
-                if let Some(autoremoval) = autoremovals.get(&package) {
-                    print_autoremoval(autoremoval);
+        // render Markdown or HTML reports with all matching items and their autoremovals
+        let mut report_packages: BTreeSet<PackageName> = self
+            .options
+            .maintainer_package
+            .iter()
+            .filter_map(|maintainer_package| {
+                PackageName::try_from(maintainer_package.as_str()).ok()
+            })
+            .collect();
+        report_packages.extend(items.items().iter().map(|item| item.source.clone()));
+        let report_autoremovals: Vec<_> = report_packages
+            .iter()
+            .filter_map(|package| autoremovals.get(package))
+            .collect();
+        let report_items: Vec<_> = items.items().iter().collect();
+        if let Some(report) = excuses_report::render(
+            self.options.format,
+            &report_items,
+            &report_autoremovals,
+            generated_date,
+        )? {
+            print!("{report}");
+        }
+
+        // the reports already include all matching items
+        if self.options.format == ReportFormat::Text {
+            for (maintainer_package, pattern) in
+                self.options.maintainer_package.iter().zip(&patterns)
+            {
+                if let Ok(package) = PackageName::try_from(maintainer_package.as_str()) {
+                    // first print the autoremoval
+                    if let Some(autoremoval) = autoremovals.get(&package) {
+                        print_autoremoval(autoremoval);
+                    }
                 }
-            }
 
-            // then print the excuses
-            for excuse in &excuses.sources {
-                if excuse.source == *maintainer_package {
+                // then print the excuses
+                for excuse in pattern.lookup(&items, &uploaders) {
                     print_excuse(excuse);
-                    continue;
+                    print_migration_estimate(excuse, generated_date);
+                    if self.options.autopkgtests {
+                        print_autopkgtests(excuse);
+                    }
+                }
+
+                // and finally the items held back by the package's tests
+                if self.options.autopkgtests {
+                    print_held_back(maintainer_package, items.items().iter().chain(&held_back));
                 }
-                if let Some(maintainer) = &excuse.maintainer
-                    && maintainer == maintainer_package
+            }
+        }
+
+        if let Some(path) = &self.options.export {
+            let mut sources = items.into_items();
+            if self.options.only_failing {
+                for policy_info in sources
+                    .iter_mut()
+                    .filter_map(|item| item.policy_info.as_mut())
                 {
-                    print_excuse(excuse);
-                    continue;
+                    policy_info.retain(|_, verdict| verdict != Verdict::Pass);
                 }
             }
+            export_excuses(
+                &Excuses {
+                    generated_date,
+                    sources,
+                },
+                path,
+                self.options.export_format,
+            )?;
         }
 
         Ok(())
@@ -126,6 +523,201 @@ impl Command for GrepExcuses<'_> {
 
 impl Downloads for GrepExcuses<'_> {
     fn downloads(&self) -> Vec<CacheEntries> {
-        vec![CacheEntries::Excuses, CacheEntries::AutoRemovals]
+        let mut downloads = vec![CacheEntries::Excuses, CacheEntries::AutoRemovals];
+        if self.options.uploaders {
+            downloads.push(CacheEntries::Sources(SuiteOrCodename::UNSTABLE));
+        }
+        downloads
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{fs, io::BufReader};
+
+    use assorted_debian_utils::excuses;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    const TEST_DATA: &str = r"---
+generated-date: 2026-10-01 10:42:13.123456+00:00
+sources:
+- item-name: zathura/amd64
+  source: zathura
+  maintainer: Sebastian Ramacher
+  is-candidate: false
+  new-version: 0.5.11-1
+  old-version: 0.5.11-1
+  policy_info:
+    age:
+      age-requirement: 5
+      current-age: 2
+      verdict: REJECTED_TEMPORARILY
+    autopkgtest:
+      zathura-pdf-poppler/0.3.3-1:
+        amd64:
+        - REGRESSION
+        - https://ci.debian.net/data/autopkgtest/testing/amd64/z/zathura-pdf-poppler/1234/log.gz
+        arm64:
+        - PASS
+      zathura/0.5.11-1:
+        amd64:
+        - NEUTRAL
+      verdict: REJECTED_PERMANENTLY
+  excuses: []
+  migration-policy-verdict: REJECTED_TEMPORARILY
+";
+
+    #[test]
+    fn export_round_trip() {
+        let excuses = excuses::from_str(TEST_DATA).unwrap();
+        let temp_dir = tempdir().unwrap();
+
+        for format in [OutputFormat::Yaml, OutputFormat::Json] {
+            let path = temp_dir.path().join(format!("excuses.{format}"));
+            export_excuses(&excuses, &path, format).unwrap();
+            let exported =
+                excuses::from_reader(BufReader::new(File::open(&path).unwrap())).unwrap();
+            assert_eq!(exported, excuses);
+            if format == OutputFormat::Json {
+                assert!(fs::read_to_string(&path).unwrap().starts_with('{'));
+            }
+        }
+    }
+
+    #[test]
+    fn autopkgtest_regressions() {
+        let excuses = excuses::from_str(TEST_DATA).unwrap();
+        let excuse = &excuses.sources[0];
+
+        let found = regressions(excuse, "zathura-pdf-poppler");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].0, "zathura-pdf-poppler/0.3.3-1");
+        assert_eq!(
+            found[0].1.keys().collect::<Vec<_>>(),
+            vec![&Architecture::Amd64]
+        );
+        assert!(regressions(excuse, "zathura").is_empty());
+        assert!(regressions(excuse, "girara").is_empty());
+    }
+
+    #[test]
+    fn estimate() {
+        let excuses = excuses::from_str(TEST_DATA).unwrap();
+        let mut excuse = excuses.sources[0].clone();
+        let generated_date = excuses.generated_date;
+
+        // blocked by the regression
+        assert_eq!(
+            migration_estimate(&excuse, generated_date),
+            MigrationEstimate::Blocked(vec!["autopkgtest: REJECTED_PERMANENTLY".into()])
+        );
+
+        // waiting for the remaining age and the autopkgtests
+        let policy_info = excuse.policy_info.as_mut().unwrap();
+        policy_info.autopkgtest.as_mut().unwrap().verdict = Verdict::RejectedTemporarily;
+        assert_eq!(
+            migration_estimate(&excuse, generated_date),
+            MigrationEstimate::Date(
+                generated_date + TimeDelta::days(3),
+                vec!["autopkgtest".into()]
+            )
+        );
+
+        // ready to migrate in the next run
+        let policy_info = excuse.policy_info.as_mut().unwrap();
+        policy_info.autopkgtest.as_mut().unwrap().verdict = Verdict::Pass;
+        policy_info.age.as_mut().unwrap().verdict = Verdict::Pass;
+        policy_info.age.as_mut().unwrap().current_age = 5;
+        excuse.migration_policy_verdict = Verdict::Pass;
+        assert_eq!(
+            migration_estimate(&excuse, generated_date),
+            MigrationEstimate::Date(generated_date + BRITNEY_RUN_INTERVAL, vec![])
+        );
+
+        excuse.migration_policy_verdict = Verdict::RejectedBlockedByAnotherItem;
+        assert_eq!(
+            migration_estimate(&excuse, generated_date),
+            MigrationEstimate::WaitingForOtherItems
+        );
+    }
+
+    #[test]
+    fn patterns() {
+        let excuses = excuses::from_str(TEST_DATA).unwrap();
+        let excuse = &excuses.sources[0];
+        let no_uploaders = HashMap::new();
+
+        for (pattern, regex) in [
+            ("zathura", false),
+            ("ZATHURA", false),
+            ("sebastian ramacher", false),
+            ("zath*", false),
+            ("zath?ra", false),
+            ("[xyz]athura", false),
+            ("Ramacher$", true),
+        ] {
+            assert!(
+                Pattern::new(pattern, regex)
+                    .unwrap()
+                    .matches_item(excuse, &no_uploaders),
+                "{pattern}"
+            );
+        }
+        for (pattern, regex) in [("zath", false), ("sebastian", false), ("^girara", true)] {
+            assert!(
+                !Pattern::new(pattern, regex)
+                    .unwrap()
+                    .matches_item(excuse, &no_uploaders),
+                "{pattern}"
+            );
+        }
+        assert!(Pattern::new("(", true).is_err());
+
+        let uploaders = HashMap::from([(
+            "zathura".try_into().unwrap(),
+            vec![Maintainer::from("Jane Doe <jane@example.org>")],
+        )]);
+        let pattern = Pattern::new("jane@example.org", false).unwrap();
+        assert!(!pattern.matches_item(excuse, &no_uploaders));
+        assert!(pattern.matches_item(excuse, &uploaders));
+    }
+
+    #[test]
+    fn lookup() {
+        let index: ExcusesIndex = excuses::from_str(TEST_DATA).unwrap().into();
+        let no_uploaders = HashMap::new();
+
+        for (pattern, regex) in [
+            ("zathura", false),
+            ("ZATHURA", false),
+            ("sebastian ramacher", false),
+            ("zath*", false),
+            ("Ramacher$", true),
+        ] {
+            assert_eq!(
+                Pattern::new(pattern, regex)
+                    .unwrap()
+                    .lookup(&index, &no_uploaders)
+                    .len(),
+                1,
+                "{pattern}"
+            );
+        }
+        assert!(
+            Pattern::new("girara", false)
+                .unwrap()
+                .lookup(&index, &no_uploaders)
+                .is_empty()
+        );
+
+        let uploaders = HashMap::from([(
+            "zathura".try_into().unwrap(),
+            vec![Maintainer::from("Jane Doe <jane@example.org>")],
+        )]);
+        let pattern = Pattern::new("jane@example.org", false).unwrap();
+        assert!(pattern.lookup(&index, &no_uploaders).is_empty());
+        assert_eq!(pattern.lookup(&index, &uploaders).len(), 1);
     }
 }
@@ -0,0 +1,370 @@
+// Copyright 2026 Sebastian Ramacher
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::{
+    collections::HashSet,
+    fmt::{self, Display},
+    fs::{self, File},
+    io::BufReader,
+};
+
+use anyhow::{Context, Result};
+use assorted_debian_utils::{
+    archive::SuiteOrCodename,
+    excuses::{self, ExcusesReader},
+    hints::{self, Hint, HintEntry, HintItem, Hints},
+    version::PackageVersion,
+};
+
+use crate::{
+    Command, Downloads,
+    cli::HintsCheckOptions,
+    config::{self, CacheEntries},
+    source_packages::SourcePackages,
+};
+
+/// Status of an item of a hint
+#[derive(Debug, PartialEq, Eq)]
+enum ItemStatus {
+    /// The hint is still required for the item
+    Active,
+    /// The item already migrated or was removed
+    Satisfied(Option<PackageVersion>),
+    /// A newer version is available in unstable
+    Superseded(PackageVersion),
+    /// The package does neither exist in testing nor in unstable
+    Unknown,
+}
+
+impl ItemStatus {
+    fn is_obsolete(&self) -> bool {
+        *self != Self::Active
+    }
+}
+
+impl Display for ItemStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Active => write!(f, "active"),
+            Self::Satisfied(Some(version)) => write!(f, "satisfied: {version} is in testing"),
+            Self::Satisfied(None) => write!(f, "satisfied: not in testing"),
+            Self::Superseded(version) => write!(f, "superseded: {version} is in unstable"),
+            Self::Unknown => write!(f, "package no longer exists"),
+        }
+    }
+}
+
+/// Check an item of a hint against the versions in testing and unstable
+///
+/// `pending` is set if the item is listed in the excuses with the same version.
+fn check_item(
+    hint: &Hint,
+    item: &HintItem,
+    testing: Option<&PackageVersion>,
+    unstable: Option<&PackageVersion>,
+    pending: bool,
+) -> ItemStatus {
+    if pending {
+        return ItemStatus::Active;
+    }
+    if testing.is_none() && unstable.is_none() {
+        return ItemStatus::Unknown;
+    }
+
+    let Some(version) = &item.version else {
+        // unversioned hints, e.g., block, only become obsolete if the package is gone
+        return ItemStatus::Active;
+    };
+    // binNMUs share the source version
+    let version = version.clone_without_binnmu_version();
+
+    match hint {
+        Hint::Remove(_) => match testing {
+            None => ItemStatus::Satisfied(None),
+            Some(testing) if *testing != version => ItemStatus::Satisfied(Some(testing.clone())),
+            Some(_) => ItemStatus::Active,
+        },
+        _ => match (testing, unstable) {
+            (Some(testing), _) if *testing >= version && item.architecture.is_none() => {
+                ItemStatus::Satisfied(Some(testing.clone()))
+            }
+            // items from (testing-)proposed-updates are not superseded by unstable
+            (_, Some(unstable)) if *unstable > version && item.suite.is_none() => {
+                ItemStatus::Superseded(unstable.clone())
+            }
+            _ => ItemStatus::Active,
+        },
+    }
+}
+
+/// Remove obsolete items from a hint
+///
+/// Returns `None` if no items are left.
+fn clean_hint<F>(hint: &Hint, is_obsolete: F) -> Option<Hint>
+where
+    F: Fn(&HintItem) -> bool,
+{
+    let retain = |items: &[HintItem]| -> Option<Vec<HintItem>> {
+        let items: Vec<_> = items
+            .iter()
+            .filter(|item| !is_obsolete(item))
+            .cloned()
+            .collect();
+        (!items.is_empty()).then_some(items)
+    };
+
+    Some(match hint {
+        Hint::Unblock(items) => Hint::Unblock(retain(items)?),
+        Hint::UnblockUdeb(items) => Hint::UnblockUdeb(retain(items)?),
+        Hint::AgeDays(days, items) => Hint::AgeDays(*days, retain(items)?),
+        Hint::Urgent(items) => Hint::Urgent(retain(items)?),
+        Hint::Block(items) => Hint::Block(retain(items)?),
+        Hint::BlockUdeb(items) => Hint::BlockUdeb(retain(items)?),
+        Hint::Remove(items) => Hint::Remove(retain(items)?),
+        Hint::Force(items) => Hint::Force(retain(items)?),
+        Hint::ForceHint(items) => Hint::ForceHint(retain(items)?),
+        Hint::Easy(items) => Hint::Easy(retain(items)?),
+        Hint::Hint(items) => Hint::Hint(retain(items)?),
+        Hint::ForceSkiptest(items) => Hint::ForceSkiptest(retain(items)?),
+        Hint::AllowSmoothUpdate(items) => Hint::AllowSmoothUpdate(retain(items)?),
+        // binary packages cannot be checked against source packages and other hints are not
+        // interpreted
+        Hint::AllowUninst(_) | Hint::Other { .. } => hint.clone(),
+    })
+}
+
+pub(crate) struct HintsCheck<'a> {
+    cache: &'a config::Cache,
+    options: HintsCheckOptions,
+}
+
+impl<'a> HintsCheck<'a> {
+    pub(crate) fn new(cache: &'a config::Cache, options: HintsCheckOptions) -> Self {
+        Self { cache, options }
+    }
+}
+
+impl Command for HintsCheck<'_> {
+    fn run(&self) -> Result<()> {
+        let hints = hints::from_reader(BufReader::new(
+            File::open(&self.options.hints)
+                .with_context(|| format!("Failed to open {}", self.options.hints.display()))?,
+        ))
+        .with_context(|| format!("Failed to parse {}", self.options.hints.display()))?;
+
+        let testing = SourcePackages::for_suite(self.cache, SuiteOrCodename::TESTING)?;
+        let unstable = SourcePackages::for_suite(self.cache, SuiteOrCodename::UNSTABLE)?;
+        // items in the excuses with the versions waiting to migrate
+        let pending: HashSet<_> =
+            ExcusesReader::new(self.cache.get_cache_bufreader("excuses.yaml")?)?
+                .map(|item| {
+                    item.map(|item| HintItem {
+                        version: item.new_version,
+                        ..item.item_name
+                    })
+                })
+                .collect::<excuses::Result<_>>()?;
+
+        let status = |hint: &Hint, item: &HintItem| {
+            check_item(
+                hint,
+                item,
+                testing.version(&item.source),
+                unstable.version(&item.source),
+                pending.contains(item),
+            )
+        };
+
+        for hint in hints.hints() {
+            for item in hint.items() {
+                let status = status(hint, item);
+                if status.is_obsolete() {
+                    println!("{} {}: {status}", hint.name(), item.hint_name());
+                }
+            }
+        }
+
+        if let Some(output) = &self.options.output {
+            let cleaned = Hints {
+                entries: hints
+                    .entries
+                    .iter()
+                    .filter_map(|entry| match entry {
+                        HintEntry::Hint(hint) => {
+                            clean_hint(hint, |item| status(hint, item).is_obsolete())
+                                .map(HintEntry::Hint)
+                        }
+                        entry => Some(entry.clone()),
+                    })
+                    .collect(),
+                finished: hints.finished.clone(),
+            };
+            fs::write(output, cleaned.to_string())
+                .with_context(|| format!("Failed to write {}", output.display()))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Downloads for HintsCheck<'_> {
+    fn downloads(&self) -> Vec<CacheEntries> {
+        let mut downloads = vec![CacheEntries::Excuses];
+        downloads.extend(CacheEntries::all_sources(SuiteOrCodename::TESTING));
+        downloads.extend(CacheEntries::all_sources(SuiteOrCodename::UNSTABLE));
+        downloads
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::{Path, PathBuf};
+
+    use assorted_debian_utils::archive::Component;
+
+    use super::*;
+    use crate::config::CachePaths;
+
+    fn version(version: &str) -> PackageVersion {
+        version.try_into().unwrap()
+    }
+
+    struct TestCache {
+        base_dir: PathBuf,
+    }
+
+    impl CachePaths for TestCache {
+        fn get_cache_path<P>(&self, path: P) -> Result<PathBuf>
+        where
+            P: AsRef<Path>,
+        {
+            Ok(self.base_dir.join(path))
+        }
+
+        fn get_package_paths(&self, _: SuiteOrCodename, _: bool) -> Result<Vec<PathBuf>> {
+            Ok(Vec::new())
+        }
+    }
+
+    #[test]
+    fn non_free() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let cache = TestCache {
+            base_dir: temp_dir.path().to_owned(),
+        };
+        for path in cache
+            .get_all_source_paths(SuiteOrCodename::TESTING)
+            .unwrap()
+        {
+            fs::write(path, "").unwrap();
+        }
+        fs::write(
+            cache
+                .get_component_source_path(SuiteOrCodename::TESTING, Component::NonFree)
+                .unwrap(),
+            "Package: unrar-nonfree\nVersion: 1:7.1.8-1\n",
+        )
+        .unwrap();
+        let testing = SourcePackages::for_suite(&cache, SuiteOrCodename::TESTING).unwrap();
+
+        let hint = Hint::try_from("unblock unrar-nonfree/1:7.1.9-1").unwrap();
+        let item = &hint.items()[0];
+        assert_eq!(
+            check_item(
+                &hint,
+                item,
+                testing.version(&item.source),
+                Some(&version("1:7.1.9-1")),
+                false
+            ),
+            ItemStatus::Active
+        );
+        assert_eq!(
+            clean_hint(&hint, |item| check_item(
+                &hint,
+                item,
+                testing.version(&item.source),
+                None,
+                false
+            )
+            .is_obsolete()),
+            Some(hint.clone())
+        );
+    }
+
+    #[test]
+    fn items() {
+        let hint = Hint::try_from("unblock zathura/0.5.11-1").unwrap();
+        let item = &hint.items()[0];
+
+        assert_eq!(
+            check_item(&hint, item, Some(&version("0.5.11-1")), None, false),
+            ItemStatus::Satisfied(Some(version("0.5.11-1")))
+        );
+        assert_eq!(
+            check_item(
+                &hint,
+                item,
+                Some(&version("0.5.10-1")),
+                Some(&version("0.5.12-1")),
+                false
+            ),
+            ItemStatus::Superseded(version("0.5.12-1"))
+        );
+        assert_eq!(
+            check_item(
+                &hint,
+                item,
+                Some(&version("0.5.10-1")),
+                Some(&version("0.5.11-1")),
+                false
+            ),
+            ItemStatus::Active
+        );
+        // still listed in the excuses
+        assert_eq!(
+            check_item(&hint, item, Some(&version("0.5.11-1")), None, true),
+            ItemStatus::Active
+        );
+        assert_eq!(
+            check_item(&hint, item, None, None, false),
+            ItemStatus::Unknown
+        );
+
+        let hint = Hint::try_from("remove zathura/0.5.11-1").unwrap();
+        let item = &hint.items()[0];
+        assert_eq!(
+            check_item(&hint, item, None, Some(&version("0.5.11-1")), false),
+            ItemStatus::Satisfied(None)
+        );
+        assert_eq!(
+            check_item(&hint, item, Some(&version("0.5.11-1")), None, false),
+            ItemStatus::Active
+        );
+
+        let hint = Hint::try_from("block zathura").unwrap();
+        let item = &hint.items()[0];
+        assert_eq!(
+            check_item(&hint, item, Some(&version("0.5.11-1")), None, false),
+            ItemStatus::Active
+        );
+    }
+
+    #[test]
+    fn clean() {
+        let hints = Hints::try_from(
+            "# comment\nunblock zathura/0.5.11-1 girara/0.4.5-1\nage-days 2 mpv/0.40.0-1\nfinished\nurgent mpv/0.39.0-1\n",
+        )
+        .unwrap();
+        let is_obsolete = |item: &HintItem| item.source != "girara";
+
+        let cleaned: Vec<_> = hints
+            .hints()
+            .filter_map(|hint| clean_hint(hint, is_obsolete))
+            .collect();
+        assert_eq!(
+            cleaned,
+            vec![Hint::try_from("unblock girara/0.4.5-1").unwrap()]
+        );
+    }
+}
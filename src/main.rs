@@ -9,27 +9,38 @@ use clap::Parser;
 use config::{CacheEntries, CacheState};
 use log::trace;
 
+mod autoremovals;
 mod binnmu_buildinfo;
 pub(crate) mod cli;
 pub(crate) mod config;
+mod excuses_diff;
+mod excuses_report;
+mod give_backs;
 mod grep_excuses;
+mod hints_check;
 mod nmu_eso;
 mod nmu_list;
 mod nmu_transition;
 mod nmu_versionskew;
 mod process_excuses;
+mod source_only;
 pub(crate) mod source_packages;
 pub(crate) mod udd_bugs;
+mod unblock_requests;
 pub(crate) mod utils;
 
+use autoremovals::AutoRemovalsReport;
 use binnmu_buildinfo::BinNMUBuildinfo;
 use cli::{DrtToolsCommands, DrtToolsOptions};
+use excuses_diff::ExcusesDiff;
 use grep_excuses::GrepExcuses;
+use hints_check::HintsCheck;
 use nmu_eso::NMUOutdatedBuiltUsing;
 use nmu_list::NMUList;
 use nmu_transition::NMUTransition;
 use nmu_versionskew::NMUVersionSkew;
 use process_excuses::ProcessExcuses;
+use unblock_requests::UnblockRequests;
 
 pub(crate) trait Downloads {
     /// Cache entries that need to be downloaded and in fresh state.
@@ -93,13 +104,35 @@ async fn main() -> Result<()> {
     trace!("base options {:?}", opts.base_options);
     trace!("command: {:?}", opts.command);
 
+    let mirrors = opts.base_options.extension_mirrors.iter().fold(
+        config::Mirrors::new(&opts.base_options.mirror),
+        |mirrors, extension_mirror| {
+            mirrors.with_extension_mirror(extension_mirror.extension, &extension_mirror.mirror)
+        },
+    );
+    let mirrors = if opts.base_options.ports {
+        mirrors.with_ports_mirror(&opts.base_options.ports_mirror)
+    } else {
+        mirrors
+    };
+    let mirrors = if let Some(snapshot) = opts.base_options.snapshot {
+        mirrors.with_snapshot(&opts.base_options.snapshot_mirror, &snapshot.to_string())
+    } else {
+        mirrors
+    };
+    let settings = config::Settings::load(opts.base_options.config.as_deref())?;
+    let http_settings = settings.http.with_options(&opts.base_options.http_options);
     let cache =
-        config::Cache::new(opts.base_options.force_download, &opts.base_options.mirror).await?;
+        config::Cache::new(opts.base_options.force_download, mirrors, &http_settings).await?;
     let command: Box<dyn AsyncCommand> =
         match opts.command {
-            DrtToolsCommands::ProcessExcuses(pe_opts) => {
-                Box::new(ProcessExcuses::new(&cache, &opts.base_options, pe_opts))
-            }
+            DrtToolsCommands::ProcessExcuses(pe_opts) => Box::new(ProcessExcuses::new(
+                &cache,
+                &opts.base_options,
+                pe_opts,
+                settings.freeze,
+                settings.rebuild,
+            )),
             DrtToolsCommands::NMUTransition(pbm_opts) => {
                 Box::new(NMUTransition::new(&cache, &opts.base_options, pbm_opts))
             }
@@ -107,6 +140,12 @@ async fn main() -> Result<()> {
                 Box::new(BinNMUBuildinfo::new(&cache, &opts.base_options, bb_opts))
             }
             DrtToolsCommands::GrepExcuses(ge_opts) => Box::new(GrepExcuses::new(&cache, ge_opts)),
+            DrtToolsCommands::ExcusesDiff(ed_opts) => Box::new(ExcusesDiff::new(&cache, ed_opts)),
+            DrtToolsCommands::HintsCheck(hc_opts) => Box::new(HintsCheck::new(&cache, hc_opts)),
+            DrtToolsCommands::UnblockRequests => Box::new(UnblockRequests::new(&cache)),
+            DrtToolsCommands::AutoRemovals(ar_opts) => {
+                Box::new(AutoRemovalsReport::new(&cache, ar_opts, settings.freeze))
+            }
             DrtToolsCommands::NMUOutdatedBuiltUsing(eso_opts) => Box::new(
                 NMUOutdatedBuiltUsing::new(&cache, &opts.base_options, eso_opts),
             ),
@@ -407,7 +407,7 @@ where
             // when looking at testing, ignore testing-proposed-updates
             Suite::Testing(_) | Suite::Unstable | Suite::Experimental => vec![self.options.suite],
             // when looking at stable, consider stable and proposed-updates
-            Suite::Stable(None) | Suite::OldStable(None) => {
+            Suite::Stable(None) | Suite::OldStable(None) | Suite::OldOldStable(None) => {
                 vec![
                     self.options.suite,
                     self.options
@@ -416,7 +416,7 @@ where
                 ]
             }
             // always consider base suite as well
-            Suite::Stable(Some(_)) | Suite::OldStable(Some(_)) => {
+            Suite::Stable(Some(_)) | Suite::OldStable(Some(_)) | Suite::OldOldStable(Some(_)) => {
                 vec![self.options.suite.without_extension(), self.options.suite]
             }
         }
@@ -428,7 +428,7 @@ where
             // when looking at testing, ignore testing-proposed-updates
             Suite::Testing(_) | Suite::Unstable | Suite::Experimental => vec![self.options.suite],
             // when looking at stable, consider stable and proposed-updates
-            Suite::Stable(None) | Suite::OldStable(None) => {
+            Suite::Stable(None) | Suite::OldStable(None) | Suite::OldOldStable(None) => {
                 vec![
                     self.options.suite,
                     self.options
@@ -436,7 +436,7 @@ where
                         .with_extension(Extension::ProposedUpdates),
                 ]
             }
-            Suite::Stable(Some(_)) | Suite::OldStable(Some(_)) => {
+            Suite::Stable(Some(_)) | Suite::OldStable(Some(_)) | Suite::OldOldStable(Some(_)) => {
                 vec![self.options.suite]
             }
         }
@@ -572,6 +572,7 @@ mod test {
     use tempfile::tempdir;
 
     use super::*;
+    use crate::cli::HttpOptions;
 
     struct TestCache {
         base_dir: PathBuf,
@@ -605,6 +606,20 @@ mod test {
             verbose: Verbosity::new(0, 1),
             buildd: String::new(),
             mirror: String::new(),
+            extension_mirrors: Vec::new(),
+            ports: false,
+            ports_mirror: String::new(),
+            snapshot: None,
+            snapshot_mirror: String::new(),
+            config: None,
+            http_options: HttpOptions {
+                proxy: None,
+                ca_certificates: Vec::new(),
+                client_identity: None,
+                connect_timeout: None,
+                read_timeout: None,
+                user_agent: None,
+            },
         };
         let options = NMUOutdatedBuiltUsingOptions {
             build_priority: 0,
@@ -752,6 +767,20 @@ Section: misc
             verbose: Verbosity::new(0, 1),
             buildd: String::new(),
             mirror: String::new(),
+            extension_mirrors: Vec::new(),
+            ports: false,
+            ports_mirror: String::new(),
+            snapshot: None,
+            snapshot_mirror: String::new(),
+            config: None,
+            http_options: HttpOptions {
+                proxy: None,
+                ca_certificates: Vec::new(),
+                client_identity: None,
+                connect_timeout: None,
+                read_timeout: None,
+                user_agent: None,
+            },
         };
         let options = NMUOutdatedBuiltUsingOptions {
             build_priority: 0,
@@ -869,6 +898,20 @@ Section: misc
             verbose: Verbosity::new(0, 1),
             buildd: String::new(),
             mirror: String::new(),
+            extension_mirrors: Vec::new(),
+            ports: false,
+            ports_mirror: String::new(),
+            snapshot: None,
+            snapshot_mirror: String::new(),
+            config: None,
+            http_options: HttpOptions {
+                proxy: None,
+                ca_certificates: Vec::new(),
+                client_identity: None,
+                connect_timeout: None,
+                read_timeout: None,
+                user_agent: None,
+            },
         };
         let options = NMUOutdatedBuiltUsingOptions {
             build_priority: 0,
@@ -1,105 +1,386 @@
 // Copyright 2021-2024 Sebastian Ramacher
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use std::{cmp::min, collections::HashSet};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashSet},
+    fmt::{self, Display},
+    fs::File,
+    io::{self, BufReader, BufWriter},
+};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use assorted_debian_utils::{
     architectures::Architecture,
     archive::{Component, SuiteOrCodename},
-    excuses::{self, ExcusesItem, PolicyInfo, Verdict},
+    excuses::{ExcusesItem, ExcusesReader, MigrationGraph, MigrationSuite, PolicyInfo, Verdict},
+    hints::{self, Hint, HintBuilder, HintItem, Unblock},
+    package::PackageName,
+    version::PackageVersion,
     wb::{BinNMU, SourceSpecifier, WBArchitecture, WBCommand, WBCommandBuilder},
 };
 use async_trait::async_trait;
+use chrono::{NaiveDate, Utc};
 use indicatif::{ProgressBar, ProgressIterator};
 use log::{debug, error, info, trace, warn};
+use serde::Serialize;
 
 use crate::{
     AsyncCommand, Downloads,
-    cli::{BaseOptions, ProcessExcusesOptions},
-    config::{self, CacheEntries, CachePaths, default_progress_template},
-    source_packages::SourcePackages,
+    cli::{BaseOptions, FreezePhase, OutputFormat, ProcessExcusesOptions},
+    config::{self, CacheEntries, CachePaths, FreezeSettings, RebuildConfig, RebuildSettings},
+    give_backs::{GiveBackAction, load_build_states, missing_builds, plan_give_back},
+    source_only,
+    source_packages::{SourcePackages, autobuilt_sources},
     utils::execute_wb_commands,
 };
 
-#[derive(Debug, PartialEq, Eq, Hash)]
+/// Components with packages that may be autobuilt
+const AUTOBUILT_COMPONENTS: [Component; 2] = [Component::NonFree, Component::NonFreeFirmware];
+
+#[derive(Debug, PartialEq, Eq)]
 enum Action {
     BinNMU(WBCommand),
-    Unblock(String),
+    /// Unblock hint together with the reason why the item is blocked
+    Unblock(Hint, String),
+    /// Give-backs and suggested dep-waits for missing builds
+    GiveBack(Vec<WBCommand>, Vec<String>),
+}
+
+/// Reason why no action is taken for an item
+#[derive(Debug, PartialEq, Eq, Serialize)]
+#[serde(
+    tag = "reason",
+    rename_all = "kebab-case",
+    rename_all_fields = "kebab-case"
+)]
+enum SkipReason {
+    Removal,
+    ProposedUpdates,
+    TestingProposedUpdates,
+    InvalidatedByOtherPackage,
+    BlockedBy {
+        blockers: Vec<String>,
+    },
+    NonMain {
+        component: Component,
+    },
+    MissingBuilds,
+    NoPolicyInfo,
+    NotRequired,
+    TooYoung {
+        current_age: u32,
+        required_age: u32,
+    },
+    AutopkgtestsNotPassing,
+    PolicyNotPassing {
+        policy: String,
+        verdict: Verdict,
+    },
+    /// Maintainer-built arch:all binaries signed by the given signer
+    ArchAll {
+        signer: String,
+    },
+    NoMaintainerBuilds,
+    InvalidCommand,
+}
+
+impl SkipReason {
+    /// Check if the item cannot be acted on at all
+    fn is_not_actionable(&self) -> bool {
+        matches!(
+            self,
+            Self::Removal
+                | Self::ProposedUpdates
+                | Self::TestingProposedUpdates
+                | Self::InvalidatedByOtherPackage
+                | Self::NonMain { .. }
+                | Self::MissingBuilds
+        )
+    }
+}
+
+impl Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Removal => write!(f, "removal"),
+            Self::ProposedUpdates => write!(f, "pu request"),
+            Self::TestingProposedUpdates => write!(f, "tpu request"),
+            Self::InvalidatedByOtherPackage => write!(f, "invalidated by other package"),
+            Self::BlockedBy { blockers } => write!(f, "blocked by {}", blockers.join(", ")),
+            Self::NonMain { component } => write!(f, "in {component} and not autobuilt"),
+            Self::MissingBuilds => write!(f, "missing builds"),
+            Self::NoPolicyInfo => write!(f, "no policy info"),
+            Self::NotRequired => write!(f, "binNMU not required"),
+            Self::TooYoung {
+                current_age,
+                required_age,
+            } => {
+                write!(
+                    f,
+                    "too young: {current_age} days (required: {required_age} days)"
+                )
+            }
+            Self::AutopkgtestsNotPassing => write!(f, "autopkgtests are not passing/are pending"),
+            Self::PolicyNotPassing { policy, verdict } => {
+                write!(f, "verdict of {policy} not passing: {verdict}")
+            }
+            Self::ArchAll { signer } => write!(f, "cannot binNMU arch: all signed by {signer}"),
+            Self::NoMaintainerBuilds => write!(f, "no architecture with maintainer built binaries"),
+            Self::InvalidCommand => write!(f, "failed to construct command"),
+        }
+    }
+}
+
+/// Decision taken for an item
+#[derive(Debug, Serialize)]
+#[serde(tag = "decision", rename_all = "kebab-case")]
+enum Decision {
+    Unblock {
+        reason: String,
+        command: String,
+    },
+    #[serde(rename = "binnmu")]
+    BinNMU {
+        command: String,
+    },
+    GiveBack {
+        commands: Vec<String>,
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        suggestions: Vec<String>,
+    },
+    Skipped {
+        #[serde(flatten)]
+        reason: SkipReason,
+        /// Human-readable description of the reason
+        #[serde(skip_serializing_if = "Option::is_none")]
+        message: Option<String>,
+    },
+}
+
+/// Decision taken for an item of the excuses for machine-readable output
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct ItemDecision {
+    item_name: String,
+    source: PackageName,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<PackageVersion>,
+    #[serde(flatten)]
+    decision: Decision,
+}
+
+impl ItemDecision {
+    fn new(item_name: String, item: &ExcusesItem, result: Result<Action, SkipReason>) -> Self {
+        Self {
+            item_name,
+            source: item.source.clone(),
+            version: item.new_version.clone(),
+            decision: match result {
+                Ok(Action::Unblock(hint, reason)) => Decision::Unblock {
+                    reason,
+                    command: hint.to_string(),
+                },
+                Ok(Action::BinNMU(command)) => Decision::BinNMU {
+                    command: command.to_string(),
+                },
+                Ok(Action::GiveBack(commands, suggestions)) => Decision::GiveBack {
+                    commands: commands.iter().map(ToString::to_string).collect(),
+                    suggestions,
+                },
+                Err(reason) => Decision::Skipped {
+                    message: Some(reason.to_string()),
+                    reason,
+                },
+            },
+        }
+    }
+}
+
+/// State of the freeze
+#[derive(Debug, Default)]
+struct Freeze {
+    phase: FreezePhase,
+    key_packages: HashSet<PackageName>,
+    /// Minimal age of packages in the current phase
+    age_requirement: Option<u32>,
+}
+
+impl Freeze {
+    /// Determine why an item from unstable requires an unblock
+    fn unblock_reason(&self, item: &ExcusesItem) -> Option<String> {
+        let policy_info = item.policy_info.as_ref()?;
+        if let Some(block) = &policy_info.block {
+            match block.verdict {
+                // already unblocked
+                Verdict::PassHinted => return None,
+                Verdict::RejectedNeedsApproval => {
+                    let mut hints: Vec<_> = block
+                        .hints
+                        .iter()
+                        .map(|(name, hint)| {
+                            format!(
+                                "{} by {}",
+                                hint.hint_type.as_deref().unwrap_or(name),
+                                hint.by.as_deref().unwrap_or("unknown")
+                            )
+                        })
+                        .collect();
+                    if hints.is_empty() {
+                        return Some("needs approval".into());
+                    }
+                    hints.sort_unstable();
+                    return Some(hints.join(", "));
+                }
+                _ => {}
+            }
+        }
+
+        let is_new = item.old_version.is_none();
+        match self.phase {
+            FreezePhase::None | FreezePhase::Transition => None,
+            FreezePhase::Soft => is_new.then(|| "new source package in the soft freeze".into()),
+            FreezePhase::Hard => {
+                if is_new {
+                    Some("new source package in the hard freeze".into())
+                } else if self.key_packages.contains(&item.source) {
+                    Some("key package in the hard freeze".into())
+                } else if !policy_info.autopkgtest.as_ref().is_some_and(|autopkgtest| {
+                    autopkgtest
+                        .results_for_package(item.source.as_ref())
+                        .next()
+                        .is_some()
+                }) {
+                    Some("no autopkgtests in the hard freeze".into())
+                } else {
+                    None
+                }
+            }
+            FreezePhase::Full => Some("full freeze".into()),
+        }
+    }
+}
+
+/// Heuristics to select rebuild candidates
+#[derive(Debug, Default)]
+struct Heuristics<'a> {
+    settings: Cow<'a, RebuildSettings>,
+    /// Items with `force-skiptest` hints
+    force_skiptest: Vec<HintItem>,
+}
+
+impl Heuristics<'_> {
+    /// Check if autopkgtest regressions of the item are covered by a `force-skiptest` hint
+    fn is_skiptest_forced(&self, item: &ExcusesItem) -> bool {
+        self.force_skiptest.iter().any(|hint_item| {
+            hint_item.source == item.source
+                && hint_item
+                    .version
+                    .as_ref()
+                    .is_none_or(|version| item.new_version.as_ref() == Some(version))
+        })
+    }
 }
 
 pub(crate) struct ProcessExcuses<'a> {
     cache: &'a config::Cache,
     base_options: &'a BaseOptions,
     options: ProcessExcusesOptions,
+    freeze_settings: FreezeSettings,
+    rebuild_config: RebuildConfig,
 }
 
 impl<'a> ProcessExcuses<'a> {
     pub(crate) fn new(
         cache: &'a config::Cache,
         base_options: &'a BaseOptions,
-        options: ProcessExcusesOptions,
+        mut options: ProcessExcusesOptions,
+        mut freeze_settings: FreezeSettings,
+        rebuild_config: RebuildConfig,
     ) -> Self {
+        if let Some(key_packages) = options.key_packages.take() {
+            freeze_settings.key_packages = Some(key_packages);
+        }
         Self {
             cache,
             base_options,
             options,
+            freeze_settings,
+            rebuild_config,
         }
     }
 
-    fn is_binnmu_required(&self, policy_info: &PolicyInfo) -> bool {
-        if let Some(b) = &policy_info.builtonbuildd {
-            if b.verdict == Verdict::Pass {
-                // nothing to do
-                trace!("no binmu required: passing");
-                return false;
+    fn is_binnmu_required(
+        item: &ExcusesItem,
+        policy_info: &PolicyInfo,
+        heuristics: &Heuristics<'_>,
+    ) -> Result<(), SkipReason> {
+        let settings = &heuristics.settings;
+        match &policy_info.builtonbuildd {
+            // nothing to do
+            None => return Err(SkipReason::NotRequired),
+            Some(b) if b.verdict == Verdict::Pass => return Err(SkipReason::NotRequired),
+            // missing builds
+            Some(b) if b.verdict == Verdict::RejectedCannotDetermineIfPermanent => {
+                return Err(SkipReason::MissingBuilds);
             }
-            if b.verdict == Verdict::RejectedCannotDetermineIfPermanent {
-                // missing builds
-                trace!("no binnmu possible: missing builds");
-                return false;
+            // cannot binNMU arch: all, so report it independent of the other policies
+            Some(b) => {
+                if let Some(signer) = b
+                    .signed_by
+                    .get(&Architecture::All)
+                    .and_then(Option::as_ref)
+                    .filter(|signer| !signer.ends_with("@buildd.debian.org"))
+                {
+                    return Err(SkipReason::ArchAll {
+                        signer: signer.clone(),
+                    });
+                }
             }
         }
         if let Some(a) = &policy_info.age
-            && !self.options.ignore_age
-            && a.current_age < min(a.age_requirement / 2, a.age_requirement - 1)
+            && !settings.is_ignored("age")
         {
-            // too young
-            trace!(
-                "no binnmu possible: too young: {} days (required: {} days)",
-                a.current_age, a.age_requirement
-            );
-            return false;
+            let minimal_age = settings.minimal_age(a.age_requirement);
+            if a.current_age < minimal_age {
+                // too young
+                return Err(SkipReason::TooYoung {
+                    current_age: a.current_age,
+                    required_age: minimal_age,
+                });
+            }
         }
         if let Some(autopkgtests) = &policy_info.autopkgtest
-            && !self.options.ignore_autopkgtests
-            && autopkgtests.verdict != Verdict::Pass
+            && !settings.is_ignored("autopkgtest")
+            && !settings
+                .autopkgtest_verdicts
+                .contains(&autopkgtests.verdict)
+            && !heuristics.is_skiptest_forced(item)
         {
-            trace!("no binnmu possible: autopkgtests are not passing/are pending");
-            return false;
+            return Err(SkipReason::AutopkgtestsNotPassing);
         }
 
         // if the others do not pass, would not migrate even if binNMUed
-        policy_info.extras.values().all(|info| {
-            if info.verdict == Verdict::Pass {
-                true
-            } else {
-                trace!("no binnmu possible: verdict not passing: {info:?}");
-                false
-            }
-        })
+        match policy_info.verdicts().find(|(name, verdict)| {
+            !matches!(*name, "age" | "builtonbuildd" | "autopkgtest")
+                && !settings.is_ignored(name)
+                && *verdict != Verdict::Pass
+        }) {
+            Some((name, verdict)) => Err(SkipReason::PolicyNotPassing {
+                policy: name.to_owned(),
+                verdict,
+            }),
+            None => Ok(()),
+        }
     }
 
     fn build_binnmu(
-        &self,
         item: &ExcusesItem,
         source_packages: &SourcePackages,
-    ) -> Option<WBCommand> {
-        let policy_info = item.policy_info.as_ref()?;
-        if !self.is_binnmu_required(policy_info) {
-            debug!("{}: binNMU not required", item.source);
-            return None;
-        }
+        heuristics: &Heuristics<'_>,
+    ) -> Result<WBCommand, SkipReason> {
+        let policy_info = item.policy_info.as_ref().ok_or(SkipReason::NoPolicyInfo)?;
+        Self::is_binnmu_required(item, policy_info, heuristics)?;
 
         // find architectures with maintainer built binaries
         let mut archs = vec![];
@@ -107,11 +388,6 @@ impl<'a> ProcessExcuses<'a> {
             if let Some(signer) = signer
                 && !signer.ends_with("@buildd.debian.org")
             {
-                if arch == &Architecture::All {
-                    // cannot binNMU arch: all
-                    debug!("{}: cannot binNMU arch: all", item.source);
-                    return None;
-                }
                 archs.push(WBArchitecture::Architecture(*arch));
             }
         }
@@ -122,7 +398,7 @@ impl<'a> ProcessExcuses<'a> {
                 item.source
             );
             trace!("{item:?}");
-            return None;
+            return Err(SkipReason::NoMaintainerBuilds);
         }
 
         let mut source_specifier = SourceSpecifier::new(&item.source);
@@ -131,101 +407,111 @@ impl<'a> ProcessExcuses<'a> {
             source_specifier.with_architectures(&archs);
         }
         if let Ok(command) = BinNMU::new(&source_specifier, "Rebuild on buildd") {
-            Some(command.build())
+            Ok(command.build())
         } else {
             error!("{}: failed to construct nmu command", item.source);
-            None
+            Err(SkipReason::InvalidCommand)
         }
     }
 
-    fn build_action(&self, item: &ExcusesItem, source_packages: &SourcePackages) -> Option<Action> {
-        if !Self::is_actionable(item) {
-            debug!("{}: not actionable", item.source);
-            return None;
-        }
+    fn build_action(
+        item: &ExcusesItem,
+        source_packages: &SourcePackages,
+        autobuilt: &HashSet<PackageName>,
+        freeze: &Freeze,
+        heuristics: &Heuristics<'_>,
+    ) -> Result<Action, SkipReason> {
+        Self::is_actionable(item)?;
 
-        if Self::is_unblock_actionable(item) {
-            Self::build_unblock(item).map(Action::Unblock)
-        } else if Self::is_binnmu_actionable(item) {
-            self.build_binnmu(item, source_packages).map(Action::BinNMU)
+        if let Some(reason) = Self::unblock_reason(item, freeze) {
+            Self::build_unblock(item).map(|hint| Action::Unblock(hint, reason))
         } else {
-            None
+            Self::is_binnmu_actionable(item, autobuilt)?;
+            Self::build_binnmu(item, source_packages, heuristics).map(Action::BinNMU)
         }
     }
 
-    fn build_unblock(item: &ExcusesItem) -> Option<String> {
-        let mut unblock = String::from("unblock ");
-        unblock.push_str(item.source.as_ref());
+    /// Drop the parts of a decided item that are neither reported nor needed to plan give-backs
+    fn strip_item(item: &mut ExcusesItem, result: &Result<Action, SkipReason>) {
+        item.excuses = Vec::new();
+        if !matches!(result, Err(SkipReason::MissingBuilds)) {
+            item.policy_info = None;
+            item.missing_builds = None;
+        }
+    }
+
+    fn build_unblock(item: &ExcusesItem) -> Result<Hint, SkipReason> {
+        let mut hint_item = HintItem::new(item.source.clone());
         // append _tpu if item is from _tpu
         if item.is_from_tpu() {
-            unblock.push_str("_tpu");
+            hint_item = hint_item.with_suite(MigrationSuite::TestingProposedUpdates);
         }
         // append version
-        unblock.push('/');
         if let Some(ref version) = item.new_version {
-            unblock.push_str(&version.to_string());
+            hint_item = hint_item.with_version(version.clone());
         } else {
             // this will never happen
             error!("{}: new-version not set", item.source);
-            return None;
+            return Err(SkipReason::InvalidCommand);
         };
 
         // append architecture for binNMUs
         if item.is_binnmu() {
-            unblock.push('/');
             if let Some(arch) = item.binnmu_arch() {
-                unblock.push_str(arch.as_ref());
+                hint_item = hint_item.with_architecture(arch);
             } else {
                 // this will never happen
                 error!("{}: binNMU but unable to extract architecture", item.source);
-                return None;
+                return Err(SkipReason::InvalidCommand);
             };
         }
 
-        Some(unblock)
+        Unblock::new(hint_item).build().map_err(|err| {
+            error!("{}: failed to construct unblock: {err}", item.source);
+            SkipReason::InvalidCommand
+        })
     }
 
-    fn is_actionable(item: &ExcusesItem) -> bool {
+    fn is_actionable(item: &ExcusesItem) -> Result<(), SkipReason> {
         if item.is_removal() {
             // skip removals
-            info!("{} not actionable: removal", item.source);
-            return false;
-        }
-        if item.is_from_pu() {
+            Err(SkipReason::Removal)
+        } else if item.is_from_pu() {
             // skip PU requests
-            info!("{} not actionable: pu request", item.source);
-            return false;
-        }
-        if let Some(true) = item.invalidated_by_other_package {
+            Err(SkipReason::ProposedUpdates)
+        } else if let Some(true) = item.invalidated_by_other_package {
             // skip otherwise blocked packages
-            info!("{} not actionable: invalided by other package", item.source);
-            return false;
+            Err(SkipReason::InvalidatedByOtherPackage)
+        } else {
+            Ok(())
         }
-
-        true
     }
 
-    fn is_binnmu_actionable(item: &ExcusesItem) -> bool {
+    fn is_binnmu_actionable(
+        item: &ExcusesItem,
+        autobuilt: &HashSet<PackageName>,
+    ) -> Result<(), SkipReason> {
         if item.is_from_tpu() {
             // skip TPU requests
-            info!("{} not actionable: tpu request", item.source);
-            return false;
+            return Err(SkipReason::TestingProposedUpdates);
         }
         match item.component {
             Some(Component::Main) | None => {}
+            // non-free packages with Autobuild: yes are built on the buildds
+            Some(component)
+                if AUTOBUILT_COMPONENTS.contains(&component)
+                    && autobuilt.contains(&item.source) => {}
             Some(component) => {
                 // skip non-free and contrib
-                info!("{} not actionable: in {}", item.source, component);
-                return false;
+                return Err(SkipReason::NonMain { component });
             }
         }
         if item.missing_builds.is_some() {
             // skip packages with missing builds
-            info!("{} not actionable: missing builds", item.source);
-            return false;
+            return Err(SkipReason::MissingBuilds);
         }
 
-        true
+        Ok(())
     }
 
     fn is_unblock_actionable(item: &ExcusesItem) -> bool {
@@ -242,44 +528,305 @@ impl<'a> ProcessExcuses<'a> {
 
         true
     }
+
+    /// Determine whether an item requires an unblock and why
+    fn unblock_reason(item: &ExcusesItem, freeze: &Freeze) -> Option<String> {
+        if Self::is_unblock_actionable(item) {
+            return Some(if item.is_from_tpu() {
+                "tpu upload".into()
+            } else {
+                "binNMU".into()
+            });
+        }
+        if item.is_from_tpu() || item.is_binnmu() {
+            return None;
+        }
+
+        let policy_info = item.policy_info.as_ref()?;
+        // if the others do not pass, would not migrate even if unblocked
+        if let Some((name, verdict)) = policy_info.verdicts().find(|(name, verdict)| {
+            !matches!(*name, "age" | "block")
+                && !matches!(verdict, Verdict::Pass | Verdict::PassHinted)
+        }) {
+            trace!(
+                "{}: no unblock possible: verdict of {name} not passing: {verdict:?}",
+                item.source
+            );
+            return None;
+        }
+
+        let mut reason = freeze.unblock_reason(item)?;
+        if let Some(age) = &policy_info.age {
+            let age_requirement = freeze
+                .age_requirement
+                .map_or(age.age_requirement, |requirement| {
+                    requirement.max(age.age_requirement)
+                });
+            if age.current_age < age_requirement {
+                reason.push_str(&format!(
+                    " (age: {} of {age_requirement} days)",
+                    age.current_age
+                ));
+            }
+        }
+        Some(reason)
+    }
+
+    /// Replace the decision for items with missing builds by give-backs and dep-waits
+    async fn plan_give_backs(
+        &self,
+        decisions: &mut BTreeMap<String, (&ExcusesItem, Result<Action, SkipReason>)>,
+    ) -> Result<()> {
+        let candidates: Vec<_> = decisions
+            .values_mut()
+            .filter(|(_, result)| matches!(result, Err(SkipReason::MissingBuilds)))
+            .collect();
+        let builds: Vec<_> = candidates
+            .iter()
+            .flat_map(|(item, _)| missing_builds(item))
+            .collect();
+        let states = load_build_states(
+            &builds,
+            self.options.wb_states.as_deref(),
+            self.base_options,
+        )
+        .await?;
+
+        for (item, result) in candidates {
+            let mut give_backs = Vec::new();
+            let mut dep_waits = Vec::new();
+            for build in missing_builds(item) {
+                let Some(state) = states.get(&build) else {
+                    debug!("{}: no build state for {}", item.source, build.2);
+                    continue;
+                };
+                match plan_give_back(item, &build, *state)? {
+                    Some(GiveBackAction::GiveBack(command)) => give_backs.push(command),
+                    Some(GiveBackAction::DepWait(Some(command))) => {
+                        dep_waits.push(command.to_string());
+                    }
+                    Some(GiveBackAction::DepWait(None)) => {
+                        dep_waits.push(format!("{}_{} on {}: {state}", build.0, build.1, build.2));
+                    }
+                    None => {}
+                }
+            }
+            if !give_backs.is_empty() || !dep_waits.is_empty() {
+                *result = Ok(Action::GiveBack(give_backs, dep_waits));
+            }
+        }
+        Ok(())
+    }
+
+    /// Select the heuristics for the phase of the freeze and load `force-skiptest` hints
+    fn load_heuristics(&self, phase: FreezePhase) -> Result<Heuristics<'_>> {
+        let force_skiptest = if let Some(path) = &self.options.hints {
+            let hints = hints::from_reader(BufReader::new(
+                File::open(path).with_context(|| format!("Failed to open {}", path.display()))?,
+            ))
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+            hints
+                .hints()
+                .filter(|hint| matches!(hint, Hint::ForceSkiptest(_)))
+                .flat_map(|hint| hint.items().iter().cloned())
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let mut settings = Cow::Borrowed(self.rebuild_config.for_phase(phase));
+        if self.options.ignore_age {
+            settings.to_mut().ignore_policies.push("age".into());
+        }
+        if self.options.ignore_autopkgtests {
+            settings.to_mut().ignore_policies.push("autopkgtest".into());
+        }
+
+        Ok(Heuristics {
+            settings,
+            force_skiptest,
+        })
+    }
+
+    /// Determine the phase of the freeze and load the key packages
+    fn load_freeze(&self, generated_date: NaiveDate) -> Result<Freeze> {
+        let phase = self
+            .options
+            .freeze_phase
+            .unwrap_or_else(|| self.freeze_settings.phase(generated_date));
+        let key_packages = if phase >= FreezePhase::Hard {
+            self.freeze_settings.load_key_packages(self.cache)?
+        } else {
+            HashSet::new()
+        };
+        info!("Freeze phase: {phase}");
+        Ok(Freeze {
+            phase,
+            key_packages,
+            age_requirement: self.freeze_settings.age_requirement(phase),
+        })
+    }
 }
 
 #[async_trait]
 impl AsyncCommand for ProcessExcuses<'_> {
     async fn run(&self) -> Result<()> {
-        let source_packages = SourcePackages::new(
-            &self
-                .cache
-                .get_package_paths(SuiteOrCodename::UNSTABLE, false)?,
-        )?;
+        // include autobuilt components to detect MA: same packages outside of main
+        let mut package_paths = self
+            .cache
+            .get_package_paths(SuiteOrCodename::UNSTABLE, false)?;
+        for component in AUTOBUILT_COMPONENTS {
+            package_paths.extend(self.cache.get_component_package_paths(
+                SuiteOrCodename::UNSTABLE,
+                component,
+                false,
+            )?);
+        }
+        let source_packages = SourcePackages::new(&package_paths)?;
         // parse excuses
-        let excuses = excuses::from_reader(self.cache.get_cache_bufreader("excuses.yaml")?)?;
-
-        // now process the excuses
-        let pb = ProgressBar::new(excuses.sources.len() as u64);
-        pb.set_style(config::default_progress_style().template(default_progress_template())?);
+        let reader = ExcusesReader::new(self.cache.get_cache_bufreader("excuses.yaml")?)?;
+        let freeze = self.load_freeze(reader.generated_date().date_naive())?;
+        let heuristics = self.load_heuristics(freeze.phase)?;
+        let autobuilt = autobuilt_sources(
+            &AUTOBUILT_COMPONENTS
+                .into_iter()
+                .map(|component| {
+                    self.cache
+                        .get_component_source_path(SuiteOrCodename::UNSTABLE, component)
+                })
+                .collect::<Result<Vec<_>>>()?,
+        )?;
+        // decide on each item while streaming the excuses and only keep the parts of the items
+        // required for the report and to resolve the blockers
+        let pb = ProgressBar::new_spinner();
         pb.set_message("Processing excuses");
-        let actions: HashSet<Action> = excuses
-            .sources
-            .iter()
-            .progress_with(pb)
-            .filter_map(|item| self.build_action(item, &source_packages))
+        let mut items = Vec::new();
+        let mut results = Vec::new();
+        for item in reader.progress_with(pb) {
+            let mut item = item?;
+            let result = if item.invalidated_by_other_package == Some(true) {
+                // decided once all blockers are known
+                Err(SkipReason::InvalidatedByOtherPackage)
+            } else {
+                Self::build_action(&item, &source_packages, &autobuilt, &freeze, &heuristics)
+            };
+            Self::strip_item(&mut item, &result);
+            items.push(item);
+            results.push(result);
+        }
+        let graph = MigrationGraph::new(&items);
+
+        // items blocked by other items are reported with the items that block them
+        let mut blocked = BTreeMap::new();
+        let mut decisions = BTreeMap::new();
+        for (item, result) in items.iter().zip(results) {
+            if item.invalidated_by_other_package != Some(true) {
+                decisions
+                    .entry(item.item_name.to_string())
+                    .or_insert((item, result));
+                continue;
+            }
+
+            let root_blockers = graph.root_blockers(&item.item_name);
+            if root_blockers.is_empty() {
+                decisions.insert(item.item_name.to_string(), (item, result));
+                continue;
+            }
+            let root_blocker_names: Vec<_> = root_blockers
+                .iter()
+                .map(|blocker| blocker.item_name.to_string())
+                .collect();
+            decisions.insert(
+                item.item_name.to_string(),
+                (
+                    item,
+                    Err(SkipReason::BlockedBy {
+                        blockers: root_blocker_names.clone(),
+                    }),
+                ),
+            );
+            blocked.insert(item.item_name.to_string(), root_blocker_names);
+        }
+
+        if self.options.give_back {
+            self.plan_give_backs(&mut decisions).await?;
+        }
+
+        // maintainer-built arch:all binaries require a source-only upload
+        let arch_all: Vec<_> = decisions
+            .values()
+            .filter_map(|(item, result)| match result {
+                Err(SkipReason::ArchAll { signer }) => Some((*item, signer.as_str())),
+                _ => None,
+            })
             .collect();
+        if let Some(path) = &self.options.source_only_mbox {
+            source_only::write_mbox(
+                BufWriter::new(
+                    File::create(path)
+                        .with_context(|| format!("Failed to create {}", path.display()))?,
+                ),
+                &arch_all,
+                Utc::now(),
+            )
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        }
+
+        if let Some(format) = self.options.format {
+            let decisions: Vec<_> = decisions
+                .into_iter()
+                .map(|(item_name, (item, result))| ItemDecision::new(item_name, item, result))
+                .collect();
+            let stdout = io::stdout().lock();
+            match format {
+                OutputFormat::Yaml => serde_yaml::to_writer(stdout, &decisions)?,
+                OutputFormat::Json => serde_json::to_writer_pretty(stdout, &decisions)?,
+            }
+            return Ok(());
+        }
+
+        println!("# Items blocked by other items");
+        for (item_name, root_blockers) in blocked {
+            println!("# {item_name}: {}", root_blockers.join(", "));
+        }
 
         println!("# Unblocks");
-        let binnmus: Vec<_> = actions
-            .into_iter()
-            .filter_map(|action| match action {
-                Action::BinNMU(command) => Some(command),
-                Action::Unblock(unblock) => {
+        let mut binnmus = Vec::new();
+        let mut give_backs = Vec::new();
+        let mut dep_waits = Vec::new();
+        for (item, result) in decisions.values() {
+            match result {
+                Ok(Action::BinNMU(command)) => binnmus.push(command.clone()),
+                Ok(Action::Unblock(unblock, reason)) => {
+                    println!("# {reason}");
                     println!("{unblock}");
-                    None
                 }
-            })
-            .collect();
+                Ok(Action::GiveBack(commands, suggestions)) => {
+                    give_backs.extend(commands.iter().cloned());
+                    dep_waits.extend(suggestions.iter().map(String::as_str));
+                }
+                Err(reason) if reason.is_not_actionable() => {
+                    info!("{} not actionable: {reason}", item.item_name);
+                }
+                Err(reason) => {
+                    debug!("{}: {reason}", item.item_name);
+                }
+            }
+        }
+
+        source_only::print_report(&arch_all);
 
         println!("# Rebuild on buildds for testing migration");
-        execute_wb_commands(binnmus, self.base_options).await
+        execute_wb_commands(binnmus, self.base_options).await?;
+
+        if self.options.give_back {
+            println!("# Give-backs for missing builds");
+            for dep_wait in dep_waits {
+                println!("# suggested: {dep_wait}");
+            }
+            execute_wb_commands(give_backs, self.base_options).await?;
+        }
+        Ok(())
     }
 }
 
@@ -289,6 +836,361 @@ impl Downloads for ProcessExcuses<'_> {
     }
 
     fn downloads(&self) -> Vec<CacheEntries> {
-        vec![CacheEntries::Packages(SuiteOrCodename::UNSTABLE)]
+        let mut downloads = vec![CacheEntries::Packages(SuiteOrCodename::UNSTABLE)];
+        downloads.extend(AUTOBUILT_COMPONENTS.into_iter().flat_map(|component| {
+            [
+                CacheEntries::ComponentSources(SuiteOrCodename::UNSTABLE, component),
+                CacheEntries::ComponentPackages(SuiteOrCodename::UNSTABLE, component),
+            ]
+        }));
+        // key packages are only relevant once the hard freeze started
+        if self
+            .options
+            .freeze_phase
+            .map_or(self.freeze_settings.hard.is_some(), |phase| {
+                phase >= FreezePhase::Hard
+            })
+        {
+            downloads.extend(self.freeze_settings.key_packages_download());
+        }
+        downloads
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use assorted_debian_utils::excuses::{self, BlockHintInfo};
+
+    use super::*;
+    use crate::config::AgeThreshold;
+
+    const TEST_DATA: &str = r"---
+generated-date: 2027-03-20 10:42:13.123456+00:00
+sources:
+- item-name: zathura
+  source: zathura
+  maintainer: Sebastian Ramacher
+  is-candidate: false
+  new-version: 0.5.11-1
+  old-version: 0.5.10-1
+  policy_info:
+    age:
+      age-requirement: 5
+      current-age: 12
+      verdict: PASS
+    autopkgtest:
+      zathura/0.5.11-1:
+        amd64:
+        - PASS
+        - https://ci.debian.net/data/autopkgtest/testing/amd64/z/zathura/1234/log.gz
+      verdict: PASS
+    block:
+      verdict: PASS
+  excuses: []
+  migration-policy-verdict: PASS
+- item-name: girara
+  source: girara
+  maintainer: Sebastian Ramacher
+  is-candidate: false
+  new-version: 0.4.5-1
+  old-version: 0.4.4-1
+  policy_info:
+    block:
+      block-udeb:
+        by: freeze
+        type: block-udeb
+      verdict: REJECTED_NEEDS_APPROVAL
+  excuses: []
+  migration-policy-verdict: REJECTED_NEEDS_APPROVAL
+- item-name: mpv
+  source: mpv
+  maintainer: Debian Multimedia Maintainers
+  is-candidate: false
+  new-version: 0.40.0-1
+  old-version: '-'
+  policy_info:
+    piuparts:
+      verdict: REJECTED_PERMANENTLY
+  excuses: []
+  migration-policy-verdict: REJECTED_PERMANENTLY
+";
+
+    #[test]
+    fn freeze_reasons() {
+        let items = excuses::from_str(TEST_DATA).unwrap().sources;
+        let reason = |item, phase, key_packages: &[&str]| {
+            ProcessExcuses::unblock_reason(
+                item,
+                &Freeze {
+                    phase,
+                    key_packages: key_packages
+                        .iter()
+                        .map(|package| PackageName::try_from(*package).unwrap())
+                        .collect(),
+                    age_requirement: FreezeSettings::default().age_requirement(phase),
+                },
+            )
+        };
+
+        assert_eq!(reason(&items[0], FreezePhase::Soft, &[]), None);
+        assert_eq!(reason(&items[0], FreezePhase::Hard, &[]), None);
+        assert_eq!(
+            reason(&items[0], FreezePhase::Hard, &["zathura"]).as_deref(),
+            Some("key package in the hard freeze (age: 12 of 20 days)")
+        );
+        assert_eq!(
+            reason(&items[0], FreezePhase::Full, &[]).as_deref(),
+            Some("full freeze")
+        );
+        assert_eq!(
+            reason(&items[1], FreezePhase::None, &[]).as_deref(),
+            Some("block-udeb by freeze")
+        );
+        let mut item = items[1].clone();
+        let block = item.policy_info.as_mut().unwrap().block.as_mut().unwrap();
+        let hint = block.hints.remove("block-udeb").unwrap();
+        block.hints.insert(
+            "block-all".into(),
+            BlockHintInfo {
+                hint_type: Some("block-all".into()),
+                by: hint.by,
+            },
+        );
+        assert_eq!(
+            reason(&item, FreezePhase::Transition, &[]).as_deref(),
+            Some("block-all by freeze")
+        );
+        // would not migrate even if unblocked
+        assert_eq!(reason(&items[2], FreezePhase::Full, &[]), None);
+    }
+
+    #[test]
+    fn decisions() {
+        let items = excuses::from_str(TEST_DATA).unwrap().sources;
+        let rebuild_items = excuses::from_str(REBUILD_DATA).unwrap().sources;
+        let freeze = Freeze {
+            phase: FreezePhase::Soft,
+            ..Default::default()
+        };
+        let source_packages = SourcePackages::new(&[] as &[&std::path::Path]).unwrap();
+        let heuristics = Heuristics::default();
+
+        let decisions: Vec<_> = [&items[1], &rebuild_items[0]]
+            .into_iter()
+            .map(|item| {
+                ItemDecision::new(
+                    item.item_name.to_string(),
+                    item,
+                    ProcessExcuses::build_action(
+                        item,
+                        &source_packages,
+                        &HashSet::new(),
+                        &freeze,
+                        &heuristics,
+                    ),
+                )
+            })
+            .collect();
+
+        let json: serde_json::Value =
+            serde_json::from_str(&serde_json::to_string(&decisions).unwrap()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!([
+                {
+                    "item-name": "girara",
+                    "source": "girara",
+                    "version": "0.4.5-1",
+                    "decision": "unblock",
+                    "reason": "block-udeb by freeze",
+                    "command": "unblock girara/0.4.5-1"
+                },
+                {
+                    "item-name": "zathura",
+                    "source": "zathura",
+                    "version": "0.5.11-1",
+                    "decision": "skipped",
+                    "reason": "too-young",
+                    "current-age": 1,
+                    "required-age": 2,
+                    "message": "too young: 1 days (required: 2 days)"
+                }
+            ])
+        );
+        assert!(
+            serde_yaml::to_string(&decisions)
+                .unwrap()
+                .contains("decision: unblock")
+        );
+    }
+
+    #[test]
+    fn strip() {
+        let mut item = excuses::from_str(REBUILD_DATA).unwrap().sources.remove(0);
+        let mut stripped = item.clone();
+        ProcessExcuses::strip_item(&mut stripped, &Err(SkipReason::NotRequired));
+        assert!(stripped.policy_info.is_none());
+        assert_eq!(stripped.item_name, item.item_name);
+
+        item.excuses.push("Too young".into());
+        ProcessExcuses::strip_item(&mut item, &Err(SkipReason::MissingBuilds));
+        assert!(item.excuses.is_empty());
+        // required to plan give-backs
+        assert!(item.policy_info.is_some());
+    }
+
+    #[test]
+    fn non_free() {
+        let mut item = excuses::from_str(TEST_DATA).unwrap().sources.remove(0);
+        item.component = Some(Component::NonFree);
+        let autobuilt = HashSet::from([PackageName::try_from("zathura").unwrap()]);
+
+        assert_eq!(
+            ProcessExcuses::is_binnmu_actionable(&item, &autobuilt),
+            Ok(())
+        );
+        assert_eq!(
+            ProcessExcuses::is_binnmu_actionable(&item, &HashSet::new()),
+            Err(SkipReason::NonMain {
+                component: Component::NonFree
+            })
+        );
+        item.component = Some(Component::Contrib);
+        assert_eq!(
+            ProcessExcuses::is_binnmu_actionable(&item, &autobuilt),
+            Err(SkipReason::NonMain {
+                component: Component::Contrib
+            })
+        );
+    }
+
+    #[test]
+    fn non_free_ma_same() {
+        let mut item = excuses::from_str(REBUILD_DATA).unwrap().sources.remove(0);
+        item.component = Some(Component::NonFree);
+        let heuristics = Heuristics {
+            settings: Cow::Owned(RebuildSettings {
+                age: AgeThreshold::Days(0),
+                ignore_policies: vec!["autopkgtest".into(), "piuparts".into()],
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("Packages_unstable_non-free_amd64");
+        std::fs::write(
+            &path,
+            "Package: libzathura1
+Source: zathura
+Version: 0.5.11-1
+Architecture: amd64
+Multi-Arch: same
+",
+        )
+        .unwrap();
+        let source_packages = SourcePackages::new(&[&path]).unwrap();
+        assert_eq!(
+            ProcessExcuses::build_binnmu(&item, &source_packages, &heuristics)
+                .unwrap()
+                .to_string(),
+            "nmu zathura_0.5.11-1 . ANY . unstable . -m \"Rebuild on buildd\""
+        );
+
+        let source_packages = SourcePackages::new(&[] as &[&std::path::Path]).unwrap();
+        assert_eq!(
+            ProcessExcuses::build_binnmu(&item, &source_packages, &heuristics)
+                .unwrap()
+                .to_string(),
+            "nmu zathura_0.5.11-1 . amd64 . unstable . -m \"Rebuild on buildd\""
+        );
+    }
+
+    const REBUILD_DATA: &str = r"---
+generated-date: 2027-03-20 10:42:13.123456+00:00
+sources:
+- item-name: zathura
+  source: zathura
+  maintainer: Sebastian Ramacher
+  is-candidate: false
+  new-version: 0.5.11-1
+  old-version: 0.5.10-1
+  policy_info:
+    age:
+      age-requirement: 5
+      current-age: 1
+      verdict: REJECTED_TEMPORARILY
+    autopkgtest:
+      verdict: REJECTED_PERMANENTLY
+    builtonbuildd:
+      signed-by:
+        amd64: sramacher@debian.org
+      verdict: REJECTED_PERMANENTLY
+    piuparts:
+      verdict: REJECTED_PERMANENTLY
+  excuses: []
+  migration-policy-verdict: REJECTED_PERMANENTLY
+";
+
+    #[test]
+    fn rebuild_heuristics() {
+        let item = &excuses::from_str(REBUILD_DATA).unwrap().sources[0];
+        let policy_info = item.policy_info.as_ref().unwrap();
+        let mut heuristics = Heuristics::default();
+        let is_binnmu_required = |heuristics: &Heuristics<'_>| {
+            ProcessExcuses::is_binnmu_required(item, policy_info, heuristics)
+        };
+
+        assert_eq!(
+            is_binnmu_required(&heuristics),
+            Err(SkipReason::TooYoung {
+                current_age: 1,
+                required_age: 2
+            })
+        );
+        heuristics.settings.to_mut().age = AgeThreshold::Days(1);
+        assert_eq!(
+            is_binnmu_required(&heuristics),
+            Err(SkipReason::AutopkgtestsNotPassing)
+        );
+        heuristics.force_skiptest = vec![HintItem::try_from("zathura/0.5.11-1").unwrap()];
+        assert_eq!(
+            is_binnmu_required(&heuristics),
+            Err(SkipReason::PolicyNotPassing {
+                policy: "piuparts".into(),
+                verdict: Verdict::RejectedPermanently
+            })
+        );
+        heuristics
+            .settings
+            .to_mut()
+            .ignore_policies
+            .push("piuparts".into());
+        assert_eq!(is_binnmu_required(&heuristics), Ok(()));
+    }
+
+    #[test]
+    fn arch_all() {
+        let mut item = excuses::from_str(REBUILD_DATA).unwrap().sources.remove(0);
+        item.policy_info
+            .as_mut()
+            .unwrap()
+            .builtonbuildd
+            .as_mut()
+            .unwrap()
+            .signed_by
+            .insert(Architecture::All, Some("sramacher@debian.org".into()));
+
+        // reported even though the item is too young and autopkgtests are failing
+        assert_eq!(
+            ProcessExcuses::is_binnmu_required(
+                &item,
+                item.policy_info.as_ref().unwrap(),
+                &Heuristics::default()
+            ),
+            Err(SkipReason::ArchAll {
+                signer: "sramacher@debian.org".into()
+            })
+        );
     }
 }
@@ -0,0 +1,255 @@
+// Copyright 2026 Sebastian Ramacher
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Report maintainer-built arch:all binaries and draft requests for source-only uploads
+
+use std::{
+    borrow::Cow,
+    io::{self, Write},
+};
+
+use assorted_debian_utils::{excuses::ExcusesItem, maintainer::Maintainer};
+use chrono::{DateTime, Utc};
+use itertools::Itertools;
+
+use crate::utils::by_maintainer;
+
+/// Maximal length of an RFC 2047 encoded word
+const MAX_ENCODED_WORD_LENGTH: usize = 75;
+
+/// Item blocked by maintainer-built arch:all binaries together with the signer
+pub(crate) type ArchAllItem<'a> = (&'a ExcusesItem, &'a str);
+
+fn format_item((item, signer): &ArchAllItem<'_>) -> String {
+    format!(
+        "{} {} (signed by {signer})",
+        item.source,
+        item.new_version
+            .as_ref()
+            .map_or_else(|| "-".into(), ToString::to_string)
+    )
+}
+
+/// Print the report of items blocked by maintainer-built arch:all binaries
+pub(crate) fn print_report(items: &[ArchAllItem<'_>]) {
+    println!("# Maintainer-built arch:all binaries");
+    for item in items {
+        println!(
+            "# {} ({})",
+            format_item(item),
+            item.0.maintainer.as_deref().unwrap_or("unknown maintainer")
+        );
+    }
+}
+
+/// Encode header text containing non-ASCII characters as RFC 2047 encoded words
+fn encode_header(value: &str) -> Cow<'_, str> {
+    if value.is_ascii() {
+        return Cow::Borrowed(value);
+    }
+
+    const PREFIX: &str = "=?utf-8?q?";
+    const SUFFIX: &str = "?=";
+    let mut words = Vec::new();
+    let mut word = String::new();
+    for c in value.chars() {
+        let mut buffer = [0u8; 4];
+        let encoded = match c {
+            ' ' => "_".to_owned(),
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '!' | '*' | '+' | '-' | '/' => c.to_string(),
+            _ => c
+                .encode_utf8(&mut buffer)
+                .bytes()
+                .map(|byte| format!("={byte:02X}"))
+                .collect(),
+        };
+        // characters must not be split across encoded words
+        if PREFIX.len() + word.len() + encoded.len() + SUFFIX.len() > MAX_ENCODED_WORD_LENGTH {
+            words.push(format!("{PREFIX}{word}{SUFFIX}"));
+            word.clear();
+        }
+        word.push_str(&encoded);
+    }
+    words.push(format!("{PREFIX}{word}{SUFFIX}"));
+    Cow::Owned(words.join(" "))
+}
+
+/// Format an address for a header encoding the name if required
+fn encode_address(maintainer: &Maintainer) -> String {
+    match &maintainer.email {
+        Some(email) => format!("{} <{email}>", encode_header(&maintainer.name)),
+        None => encode_header(&maintainer.name).into_owned(),
+    }
+}
+
+/// Write a draft for each maintainer asking for source-only uploads
+///
+/// The drafts are addressed to the maintainer if the email address is known and otherwise to the
+/// package tracker. The signers of the binaries are put in Cc.
+pub(crate) fn write_mbox<W>(
+    mut writer: W,
+    items: &[ArchAllItem<'_>],
+    date: DateTime<Utc>,
+) -> io::Result<()>
+where
+    W: Write,
+{
+    for (maintainer, items) in by_maintainer(items, "", |(item, _)| *item) {
+        let maintainer = Maintainer::from(maintainer);
+        let to: Vec<_> = if maintainer.email.is_some() {
+            vec![encode_address(&maintainer)]
+        } else {
+            items
+                .iter()
+                .map(|(item, _)| format!("{}@packages.debian.org", item.source))
+                .unique()
+                .collect()
+        };
+        let cc: Vec<_> = items
+            .iter()
+            .map(|(_, signer)| *signer)
+            .filter(|signer| !maintainer.matches(signer))
+            .unique()
+            .map(|signer| encode_address(&Maintainer::from(signer)))
+            .collect();
+        let subject = match items.as_slice() {
+            [(item, _)] => format!(
+                "{}: source-only upload required for testing migration",
+                item.source
+            ),
+            _ => format!(
+                "Source-only uploads required for testing migration of {} packages",
+                items.len()
+            ),
+        };
+
+        writeln!(
+            writer,
+            "From drt-tools {}",
+            date.format("%a %b %e %H:%M:%S %Y")
+        )?;
+        writeln!(writer, "To: {}", to.join(", "))?;
+        if !cc.is_empty() {
+            writeln!(writer, "Cc: {}", cc.join(", "))?;
+        }
+        writeln!(writer, "Subject: {}", encode_header(&subject))?;
+        writeln!(writer, "Date: {}", date.to_rfc2822())?;
+        writeln!(writer, "MIME-Version: 1.0")?;
+        writeln!(writer, "Content-Type: text/plain; charset=utf-8")?;
+        writeln!(writer)?;
+
+        let mut body = String::new();
+        if maintainer.name.is_empty() {
+            body.push_str("Hi,\n\n");
+        } else {
+            body.push_str(&format!("Hi {},\n\n", maintainer.name));
+        }
+        body.push_str(
+            "the following packages contain maintainer-built binaries for architecture all and\n\
+             can therefore not migrate to testing. As arch:all binaries cannot be rebuilt via\n\
+             binNMUs, please do a source-only upload:\n\n",
+        );
+        for item in &items {
+            body.push_str(&format!(" * {}\n", format_item(item)));
+        }
+        body.push_str("\nCheers\n");
+
+        for line in body.lines() {
+            // escape lines that would be interpreted as start of a new message
+            if line.starts_with("From ") {
+                write!(writer, ">")?;
+            }
+            writeln!(writer, "{line}")?;
+        }
+        writeln!(writer)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use assorted_debian_utils::excuses;
+
+    use super::*;
+
+    const TEST_DATA: &str = r"---
+generated-date: 2026-10-01 10:42:13.123456+00:00
+sources:
+- item-name: zathura
+  source: zathura
+  maintainer: Sebastian Ramacher
+  is-candidate: false
+  new-version: 0.5.11-1
+  old-version: 0.5.10-1
+  excuses: []
+  migration-policy-verdict: REJECTED_PERMANENTLY
+- item-name: girara
+  source: girara
+  maintainer: Sebastian Ramacher
+  is-candidate: false
+  new-version: 0.4.5-1
+  old-version: 0.4.4-1
+  excuses: []
+  migration-policy-verdict: REJECTED_PERMANENTLY
+- item-name: mpv
+  source: mpv
+  maintainer: Debian Multimedia Maintainers <debian-multimedia@lists.debian.org>
+  is-candidate: false
+  new-version: 0.40.0-1
+  old-version: 0.39.0-1
+  excuses: []
+  migration-policy-verdict: REJECTED_PERMANENTLY
+";
+
+    #[test]
+    fn mbox() {
+        let excuses = excuses::from_str(TEST_DATA).unwrap();
+        let items: Vec<_> = vec![
+            (&excuses.sources[0], "sramacher@debian.org"),
+            (&excuses.sources[1], "sramacher@debian.org"),
+            (&excuses.sources[2], "debian-multimedia@lists.debian.org"),
+        ];
+
+        let mut output = Vec::new();
+        write_mbox(&mut output, &items, excuses.generated_date).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        let mails: Vec<_> = output
+            .split("From drt-tools ")
+            .filter(|mail| !mail.is_empty())
+            .collect();
+        assert_eq!(mails.len(), 2);
+        assert!(
+            mails[0].contains(
+                "To: Debian Multimedia Maintainers <debian-multimedia@lists.debian.org>\n"
+            )
+        );
+        assert!(!mails[0].contains("Cc:"));
+        assert!(
+            mails[0].contains("Subject: mpv: source-only upload required for testing migration\n")
+        );
+        assert!(mails[1].contains("To: zathura@packages.debian.org, girara@packages.debian.org\n"));
+        assert!(mails[1].contains("Cc: sramacher@debian.org\n"));
+        assert!(mails[1].contains("Hi Sebastian Ramacher,"));
+        assert!(mails[1].contains(" * girara 0.4.5-1 (signed by sramacher@debian.org)\n"));
+    }
+
+    #[test]
+    fn encoded_headers() {
+        assert_eq!(encode_header("zathura"), "zathura");
+        assert_eq!(
+            encode_address(&Maintainer::from("Jörg Müller <joerg@example.org>")),
+            "=?utf-8?q?J=C3=B6rg_M=C3=BCller?= <joerg@example.org>"
+        );
+
+        let long = "ä".repeat(20);
+        let encoded = encode_header(&long);
+        let words: Vec<_> = encoded.split(' ').collect();
+        assert_eq!(words.len(), 2);
+        assert!(
+            words
+                .iter()
+                .all(|word| word.len() <= MAX_ENCODED_WORD_LENGTH && word.ends_with("?="))
+        );
+    }
+}
@@ -3,7 +3,7 @@
 
 use std::{
     borrow::Borrow,
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fmt::{self, Display},
     hash::Hash,
     path::Path,
@@ -11,7 +11,8 @@ use std::{
 
 use anyhow::Result;
 use assorted_debian_utils::{
-    archive::MultiArch,
+    archive::{MultiArch, SuiteOrCodename},
+    maintainer::{self, Maintainer},
     package::{PackageName, VersionedPackage},
     rfc822_like,
     version::PackageVersion,
@@ -22,7 +23,7 @@ use serde::{
     de::{self, DeserializeOwned},
 };
 
-use crate::config;
+use crate::config::{self, CachePaths};
 
 /// Source package name with optional version in parenthesis
 #[derive(Debug, PartialEq, Eq)]
@@ -245,6 +246,14 @@ impl SourcePackages {
         Ok(Self(all_sources))
     }
 
+    /// Load the source packages from the Sources files of all components of a suite
+    pub fn for_suite<C>(cache: &C, suite: SuiteOrCodename) -> Result<Self>
+    where
+        C: CachePaths,
+    {
+        Self::new_with_source(&cache.get_all_source_paths(suite)?, &[] as &[&Path])
+    }
+
     /// Check if a source package builds an MA: same binary package
     ///
     /// Returns false if the source package does not exist.
@@ -271,6 +280,53 @@ impl SourcePackages {
     }
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct SourceUploaders {
+    package: PackageName,
+    uploaders: Option<String>,
+}
+
+/// Collect the uploaders of all source packages from Sources files
+pub fn uploaders<P>(paths: &[P]) -> Result<HashMap<PackageName, Vec<Maintainer>>>
+where
+    P: AsRef<Path>,
+{
+    let mut all_uploaders = HashMap::new();
+    for path in paths {
+        for source_package in parse_packages::<SourceUploaders>(path.as_ref())? {
+            if let Some(uploaders) = source_package.uploaders {
+                all_uploaders.insert(source_package.package, maintainer::parse_list(&uploaders));
+            }
+        }
+    }
+    Ok(all_uploaders)
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct SourceAutobuild {
+    package: PackageName,
+    autobuild: Option<String>,
+}
+
+/// Collect the source packages that allow autobuilding, i.e., with `Autobuild: yes`, from Sources
+/// files
+pub fn autobuilt_sources<P>(paths: &[P]) -> Result<HashSet<PackageName>>
+where
+    P: AsRef<Path>,
+{
+    let mut autobuilt = HashSet::new();
+    for path in paths {
+        for source_package in parse_packages::<SourceAutobuild>(path.as_ref())? {
+            if source_package.autobuild.as_deref() == Some("yes") {
+                autobuilt.insert(source_package.package);
+            }
+        }
+    }
+    Ok(autobuilt)
+}
+
 fn parse_packages<P>(path: &Path) -> Result<impl Iterator<Item = P>>
 where
     P: DeserializeOwned,
@@ -324,4 +380,28 @@ SHA256: 741c4058fc3cc23d697f6e4194e7fc8aef2d6d6628184e333316194c80bdbf17",
             }
         );
     }
+
+    #[test]
+    fn source_autobuild() {
+        let package = rfc822_like::from_str::<SourceAutobuild>(
+            "Package: nvidia-graphics-drivers
+Binary: nvidia-driver, nvidia-kernel-dkms
+Version: 550.163.01-2
+Maintainer: Debian NVIDIA Maintainers <pkg-nvidia-devel@lists.alioth.debian.org>
+Architecture: amd64 arm64
+Autobuild: yes
+Section: non-free/libs",
+        )
+        .unwrap();
+        assert_eq!(package.autobuild.as_deref(), Some("yes"));
+
+        let package = rfc822_like::from_str::<SourceAutobuild>(
+            "Package: unrar-nonfree
+Version: 1:7.1.8-1
+Architecture: any
+Section: non-free/utils",
+        )
+        .unwrap();
+        assert!(package.autobuild.is_none());
+    }
 }
@@ -8,10 +8,7 @@ use std::{
 };
 
 use anyhow::Result;
-use assorted_debian_utils::{
-    archive::{Codename, SuiteOrCodename},
-    package::PackageName,
-};
+use assorted_debian_utils::{archive::SuiteOrCodename, package::PackageName};
 use serde::{Deserialize, Deserializer, de};
 
 use crate::config::Cache;
@@ -98,10 +95,15 @@ pub struct UDDBugs {
 
 impl UDDBugs {
     pub fn load_for_codename(cache: &Cache, suite: SuiteOrCodename) -> Result<Self> {
-        let codename = Codename::from(suite);
+        let codename = cache.codename(suite);
         load_bugs_from_reader(cache.get_cache_bufreader(format!("udd-ftbfs-bugs-{codename}.yaml"))?)
     }
 
+    /// Load the unblock requests filed against `release.debian.org`
+    pub fn load_unblock_requests(cache: &Cache) -> Result<Self> {
+        load_bugs_from_reader(cache.get_cache_bufreader("udd-unblock-requests.yaml")?)
+    }
+
     fn new(bugs: Vec<UDDBug>) -> Self {
         let mut udd_bugs = Self {
             bugs,
@@ -121,6 +123,10 @@ impl UDDBugs {
         udd_bugs
     }
 
+    pub fn bugs(&self) -> &[UDDBug] {
+        &self.bugs
+    }
+
     pub fn bugs_for_source(&self, source: &PackageName) -> Option<Vec<&UDDBug>> {
         self.source_index
             .get(source)
@@ -128,7 +134,7 @@ impl UDDBugs {
     }
 }
 
-fn load_bugs_from_reader(reader: impl Read) -> Result<UDDBugs> {
+pub(crate) fn load_bugs_from_reader(reader: impl Read) -> Result<UDDBugs> {
     serde_yaml::from_reader(reader)
         .map_err(Into::into)
         .map(UDDBugs::new)
@@ -0,0 +1,407 @@
+// Copyright 2026 Sebastian Ramacher
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::{
+    fmt::{self, Display},
+    sync::LazyLock,
+};
+
+use anyhow::Result;
+use assorted_debian_utils::{
+    archive::SuiteOrCodename,
+    excuses::{ExcusesIndex, ExcusesItem, ExcusesReader, Verdict},
+    package::PackageName,
+    version::PackageVersion,
+};
+use regex::Regex;
+
+use crate::{
+    Command, Downloads,
+    config::{self, CacheEntries},
+    source_packages::SourcePackages,
+    udd_bugs::{UDDBug, UDDBugs},
+};
+
+/// Source package and version in the title of unblock requests, e.g., `unblock: zathura/0.5.11-1`
+static REQUESTED_ITEM: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^\s*unblock(?:-udeb)?\b[^:]*:\s*(?:pre-approval:\s*)?([a-z0-9][a-z0-9+.-]+)/([0-9][A-Za-z0-9.+~:-]*)")
+        .unwrap()
+});
+
+/// An unblock request
+#[derive(Debug)]
+struct UnblockRequest<'a> {
+    bug: &'a UDDBug,
+    source: PackageName,
+    version: PackageVersion,
+}
+
+impl<'a> UnblockRequest<'a> {
+    /// Extract the requested item from the bug's title
+    fn new(bug: &'a UDDBug) -> Option<Self> {
+        let captures = REQUESTED_ITEM.captures(&bug.title)?;
+        Some(Self {
+            bug,
+            source: PackageName::try_from(&captures[1]).ok()?,
+            version: PackageVersion::try_from(&captures[2]).ok()?,
+        })
+    }
+}
+
+/// Status of an unblock request
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum RequestStatus {
+    /// All policies except the freeze pass and the item needs an unblock
+    NeedsUnblock,
+    /// Waiting for the age requirement, also if already unblocked
+    WaitingForAge(u32, u32),
+    /// Waiting for the autopkgtests to pass, also if already unblocked
+    WaitingForAutopkgtests,
+    /// Blocked by other policies
+    Blocked(Vec<String>),
+    /// Already unblocked and nothing else is pending
+    Unblocked,
+    /// Not blocked by the freeze
+    NotFrozen,
+    /// The requested version is not listed in the excuses
+    NotInExcuses,
+    /// The requested version is not yet in unstable
+    NotInUnstable(Option<PackageVersion>),
+    /// A newer version is in unstable
+    Outdated(PackageVersion),
+    /// The requested version already migrated
+    Migrated(PackageVersion),
+}
+
+impl RequestStatus {
+    fn section(&self) -> &'static str {
+        match self {
+            Self::NeedsUnblock => "Ready to unblock",
+            Self::WaitingForAge(_, _) => "Waiting for age",
+            Self::WaitingForAutopkgtests => "Waiting for autopkgtests",
+            Self::Blocked(_) => "Blocked by other policies",
+            Self::Unblocked => "Unblocked, waiting for migration",
+            Self::NotFrozen => "Not blocked by the freeze",
+            Self::NotInExcuses => "Not listed in excuses",
+            Self::NotInUnstable(_) => "Not yet in unstable",
+            Self::Outdated(_) => "Outdated requests",
+            Self::Migrated(_) => "Already migrated",
+        }
+    }
+}
+
+impl Display for RequestStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NeedsUnblock => write!(f, "needs unblock"),
+            Self::WaitingForAge(current, required) => {
+                write!(f, "{current} of {required} days")
+            }
+            Self::WaitingForAutopkgtests => write!(f, "autopkgtests not passing"),
+            Self::Blocked(policies) => write!(f, "{}", policies.join(", ")),
+            Self::Unblocked => write!(f, "unblocked"),
+            Self::NotFrozen => write!(f, "not frozen"),
+            Self::NotInExcuses => write!(f, "not in excuses"),
+            Self::NotInUnstable(Some(version)) => write!(f, "{version} in unstable"),
+            Self::NotInUnstable(None) => write!(f, "not in unstable"),
+            Self::Outdated(version) => write!(f, "{version} in unstable"),
+            Self::Migrated(version) => write!(f, "{version} in testing"),
+        }
+    }
+}
+
+/// Determine the status of a request from the versions in testing and unstable and the excuses
+fn request_status<'a>(
+    request: &UnblockRequest<'_>,
+    testing: Option<&PackageVersion>,
+    unstable: Option<&PackageVersion>,
+    mut excuses: impl Iterator<Item = &'a ExcusesItem>,
+) -> RequestStatus {
+    if let Some(testing) = testing
+        && *testing >= request.version
+    {
+        return RequestStatus::Migrated(testing.clone());
+    }
+    match unstable {
+        Some(unstable) if *unstable > request.version => {
+            return RequestStatus::Outdated(unstable.clone());
+        }
+        Some(unstable) if *unstable < request.version => {
+            return RequestStatus::NotInUnstable(Some(unstable.clone()));
+        }
+        None => return RequestStatus::NotInUnstable(None),
+        _ => {}
+    }
+
+    let Some(policy_info) = excuses
+        .find(|item| item.new_version.as_ref() == Some(&request.version))
+        .map(|item| item.policy_info.as_ref())
+    else {
+        return RequestStatus::NotInExcuses;
+    };
+    let Some(policy_info) = policy_info else {
+        return RequestStatus::NotFrozen;
+    };
+
+    let blocked: Vec<_> = policy_info
+        .verdicts()
+        .filter(|(name, verdict)| {
+            !matches!(*name, "age" | "autopkgtest" | "block")
+                && !matches!(verdict, Verdict::Pass | Verdict::PassHinted)
+        })
+        .map(|(name, verdict)| format!("{name}: {verdict}"))
+        .collect();
+    if !blocked.is_empty() {
+        return RequestStatus::Blocked(blocked);
+    }
+    let unblocked = match policy_info.block.as_ref().map(|block| block.verdict) {
+        Some(Verdict::RejectedNeedsApproval) => false,
+        Some(Verdict::PassHinted) => true,
+        _ => return RequestStatus::NotFrozen,
+    };
+    // unblocked items might still wait for age or autopkgtests
+    if let Some(age) = &policy_info.age
+        && age.verdict == Verdict::RejectedTemporarily
+    {
+        return RequestStatus::WaitingForAge(age.current_age, age.age_requirement);
+    }
+    if let Some(autopkgtest) = &policy_info.autopkgtest
+        && !matches!(autopkgtest.verdict, Verdict::Pass | Verdict::PassHinted)
+    {
+        return RequestStatus::WaitingForAutopkgtests;
+    }
+    if unblocked {
+        RequestStatus::Unblocked
+    } else {
+        RequestStatus::NeedsUnblock
+    }
+}
+
+pub(crate) struct UnblockRequests<'a> {
+    cache: &'a config::Cache,
+}
+
+impl<'a> UnblockRequests<'a> {
+    pub(crate) fn new(cache: &'a config::Cache) -> Self {
+        Self { cache }
+    }
+}
+
+impl Command for UnblockRequests<'_> {
+    fn run(&self) -> Result<()> {
+        let bugs = UDDBugs::load_unblock_requests(self.cache)?;
+        let testing = SourcePackages::for_suite(self.cache, SuiteOrCodename::TESTING)?;
+        let unstable = SourcePackages::for_suite(self.cache, SuiteOrCodename::UNSTABLE)?;
+        let excuses = ExcusesReader::new(self.cache.get_cache_bufreader("excuses.yaml")?)?
+            .collect::<Result<ExcusesIndex, _>>()?;
+
+        let mut requests = Vec::new();
+        let mut unparsable = Vec::new();
+        for bug in bugs.bugs() {
+            let Some(request) = UnblockRequest::new(bug) else {
+                unparsable.push(bug);
+                continue;
+            };
+            let status = request_status(
+                &request,
+                testing.version(&request.source),
+                unstable.version(&request.source),
+                excuses.by_source(request.source.as_ref()),
+            );
+            requests.push((status, request));
+        }
+        // group by status
+        requests.sort_by(|(lhs, lhs_request), (rhs, rhs_request)| {
+            lhs.cmp(rhs)
+                .then_with(|| lhs_request.source.cmp(&rhs_request.source))
+        });
+
+        let mut section = None;
+        for (status, request) in requests {
+            if section != Some(status.section()) {
+                section = Some(status.section());
+                println!("# {}", status.section());
+            }
+            println!(
+                "#{} {}/{}: {status}",
+                request.bug.id, request.source, request.version
+            );
+        }
+        if !unparsable.is_empty() {
+            println!("# Unable to parse request");
+            for bug in unparsable {
+                println!("#{}: {}", bug.id, bug.title);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Downloads for UnblockRequests<'_> {
+    fn downloads(&self) -> Vec<CacheEntries> {
+        let mut downloads = vec![CacheEntries::Excuses, CacheEntries::UnblockRequests];
+        downloads.extend(CacheEntries::all_sources(SuiteOrCodename::TESTING));
+        downloads.extend(CacheEntries::all_sources(SuiteOrCodename::UNSTABLE));
+        downloads
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use assorted_debian_utils::excuses;
+
+    use super::*;
+    use crate::udd_bugs::load_bugs_from_reader;
+
+    const BUGS: &str = r"
+---
+- id: 1100001
+  package: release.debian.org
+  source: release.debian.org
+  severity: normal
+  title: 'unblock: zathura/0.5.11-1'
+- id: 1100002
+  package: release.debian.org
+  source: release.debian.org
+  severity: normal
+  title: 'unblock-udeb: girara/0.4.5-1'
+- id: 1100003
+  package: release.debian.org
+  source: release.debian.org
+  severity: normal
+  title: 'unblock (pre-approval): mpv/0.40.0-2'
+- id: 1100004
+  package: release.debian.org
+  source: release.debian.org
+  severity: normal
+  title: 'please unblock mpv'
+";
+
+    const EXCUSES: &str = r"---
+generated-date: 2026-10-01 10:42:13.123456+00:00
+sources:
+- item-name: zathura
+  source: zathura
+  maintainer: Sebastian Ramacher
+  is-candidate: false
+  new-version: 0.5.11-1
+  old-version: 0.5.10-1
+  policy_info:
+    age:
+      age-requirement: 20
+      current-age: 2
+      verdict: REJECTED_TEMPORARILY
+    block:
+      verdict: REJECTED_NEEDS_APPROVAL
+  excuses: []
+  migration-policy-verdict: REJECTED_NEEDS_APPROVAL
+";
+
+    fn version(version: &str) -> PackageVersion {
+        version.try_into().unwrap()
+    }
+
+    #[test]
+    fn requests() {
+        let bugs = load_bugs_from_reader(BUGS.as_bytes()).unwrap();
+        let requests: Vec<_> = bugs.bugs().iter().map(UnblockRequest::new).collect();
+
+        let request = requests[0].as_ref().unwrap();
+        assert_eq!(request.source, "zathura");
+        assert_eq!(request.version, version("0.5.11-1"));
+        assert_eq!(requests[1].as_ref().unwrap().source, "girara");
+        assert_eq!(requests[2].as_ref().unwrap().version, version("0.40.0-2"));
+        assert!(requests[3].is_none());
+    }
+
+    #[test]
+    fn status() {
+        let bugs = load_bugs_from_reader(BUGS.as_bytes()).unwrap();
+        let request = UnblockRequest::new(&bugs.bugs()[0]).unwrap();
+        let mut excuses = excuses::from_str(EXCUSES).unwrap();
+
+        assert_eq!(
+            request_status(
+                &request,
+                Some(&version("0.5.10-1")),
+                Some(&version("0.5.11-1")),
+                excuses.sources.iter()
+            ),
+            RequestStatus::WaitingForAge(2, 20)
+        );
+        assert_eq!(
+            request_status(
+                &request,
+                Some(&version("0.5.11-1")),
+                Some(&version("0.5.11-1")),
+                [].iter()
+            ),
+            RequestStatus::Migrated(version("0.5.11-1"))
+        );
+        assert_eq!(
+            request_status(
+                &request,
+                Some(&version("0.5.10-1")),
+                Some(&version("0.5.12-1")),
+                [].iter()
+            ),
+            RequestStatus::Outdated(version("0.5.12-1"))
+        );
+        assert_eq!(
+            request_status(
+                &request,
+                Some(&version("0.5.10-1")),
+                Some(&version("0.5.10-1")),
+                [].iter()
+            ),
+            RequestStatus::NotInUnstable(Some(version("0.5.10-1")))
+        );
+
+        let policy_info = excuses.sources[0].policy_info.as_mut().unwrap();
+        policy_info.age.as_mut().unwrap().verdict = Verdict::Pass;
+        assert_eq!(
+            request_status(
+                &request,
+                Some(&version("0.5.10-1")),
+                Some(&version("0.5.11-1")),
+                excuses.sources.iter()
+            ),
+            RequestStatus::NeedsUnblock
+        );
+
+        let policy_info = excuses.sources[0].policy_info.as_mut().unwrap();
+        policy_info.block.as_mut().unwrap().verdict = Verdict::PassHinted;
+        assert_eq!(
+            request_status(
+                &request,
+                Some(&version("0.5.10-1")),
+                Some(&version("0.5.11-1")),
+                excuses.sources.iter()
+            ),
+            RequestStatus::Unblocked
+        );
+        // unblocked, but still too young
+        let policy_info = excuses.sources[0].policy_info.as_mut().unwrap();
+        policy_info.age.as_mut().unwrap().verdict = Verdict::RejectedTemporarily;
+        assert_eq!(
+            request_status(
+                &request,
+                Some(&version("0.5.10-1")),
+                Some(&version("0.5.11-1")),
+                excuses.sources.iter()
+            ),
+            RequestStatus::WaitingForAge(2, 20)
+        );
+        let policy_info = excuses.sources[0].policy_info.as_mut().unwrap();
+        policy_info.block = None;
+        assert_eq!(
+            request_status(
+                &request,
+                Some(&version("0.5.10-1")),
+                Some(&version("0.5.11-1")),
+                excuses.sources.iter()
+            ),
+            RequestStatus::NotFrozen
+        );
+    }
+}
@@ -1,13 +1,40 @@
 // Copyright 2024 Sebastian Ramacher
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+use std::collections::BTreeMap;
+
 use anyhow::{Context, Result};
-use assorted_debian_utils::wb::{Error, WBCommand};
+use assorted_debian_utils::{
+    excuses::ExcusesItem,
+    wb::{Error, WBCommand},
+};
 use openssh::{KnownHosts, Session, Stdio};
 use tokio::io::AsyncWriteExt;
 
 use crate::cli::BaseOptions;
 
+/// Group items by the maintainer of their excuses item
+///
+/// Items without a maintainer are grouped under `unknown`.
+pub(crate) fn by_maintainer<'a, T, F>(
+    items: &[T],
+    unknown: &'a str,
+    excuses_item: F,
+) -> BTreeMap<&'a str, Vec<T>>
+where
+    T: Copy,
+    F: Fn(&T) -> &'a ExcusesItem,
+{
+    let mut groups: BTreeMap<_, Vec<_>> = BTreeMap::new();
+    for item in items {
+        groups
+            .entry(excuses_item(item).maintainer.as_deref().unwrap_or(unknown))
+            .or_default()
+            .push(*item);
+    }
+    groups
+}
+
 pub(crate) async fn execute_wb_commands<I>(commands: I, options: &BaseOptions) -> Result<()>
 where
     I: IntoIterator<Item = WBCommand>,
@@ -45,3 +72,47 @@ where
         .context("Failed to wait on child process")?;
     Ok(())
 }
+
+/// Execute `wb` commands in a single invocation and collect their output
+///
+/// The output of all commands is returned in the order of the commands.
+pub(crate) async fn query_wb_commands<I>(commands: I, options: &BaseOptions) -> Result<String>
+where
+    I: IntoIterator<Item = WBCommand>,
+{
+    let session = Session::connect_mux(&options.buildd, KnownHosts::Strict).await?;
+
+    let mut proc = session
+        .command("wb")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .await
+        .context("Failed to spawn process")?;
+
+    if let Some(mut stdin) = proc.stdin().take() {
+        for command in commands {
+            stdin
+                .write_all(format!("{command}\n").as_bytes())
+                .await
+                .with_context(|| format!("Failed to write wb command to stdin: {command}"))?;
+        }
+    } else {
+        return Err(Error::ExecutionError).context("Unable to write to stdin.");
+    }
+    let output = proc
+        .wait_with_output()
+        .await
+        .context("Failed to wait on child process")?;
+    if !output.status.success() {
+        return Err(Error::ExecutionError).with_context(|| {
+            format!(
+                "wb exited with {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}